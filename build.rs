@@ -0,0 +1,25 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+
+// With the `embed-inputs` feature, generate a table of whichever puzzle inputs exist in input/ so
+// that they are compiled into the binary.  Without it the table is empty.
+fn main() {
+    let mut table = String::from("&[\n");
+    if env::var_os("CARGO_FEATURE_EMBED_INPUTS").is_some() {
+        let dir = Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap()).join("input");
+        println!("cargo:rerun-if-changed={}", dir.display());
+        for day in 1..=25 {
+            let path = dir.join(format!("day{}.txt", day));
+            if path.exists() {
+                table += &format!("    ({}, include_str!({:?})),\n", day, path);
+            }
+        }
+    } else {
+        println!("cargo:rerun-if-changed=build.rs");
+    }
+    table += "]\n";
+
+    let out = Path::new(&env::var("OUT_DIR").unwrap()).join("embedded_inputs.rs");
+    fs::write(out, table).unwrap();
+}
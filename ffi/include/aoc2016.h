@@ -0,0 +1,55 @@
+#ifndef AOC2016_H
+#define AOC2016_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#define AOC2016_OK 0
+
+#define AOC2016_ERR_NULL_POINTER 1
+
+#define AOC2016_ERR_INVALID_UTF8 2
+
+#define AOC2016_ERR_UNKNOWN_DAY 3
+
+#define AOC2016_ERR_UNKNOWN_PART 4
+
+#define AOC2016_ERR_SOLVER_PANICKED 5
+
+#define AOC2016_ERR_INVALID_INPUT 6
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Solve one part of a day's puzzle.
+ *
+ * `input` points to `input_len` bytes of UTF-8 puzzle input.  On return `*output` is set to a
+ * newly allocated NUL-terminated string: the answer if the result is `AOC2016_OK`, otherwise a
+ * description of the error.  It must be released with `aoc2016_free_string`.
+ *
+ * # Safety
+ *
+ * `input` must be valid for reads of `input_len` bytes and `output` must be valid for a write.
+ */
+int aoc2016_solve(uint32_t day,
+                  uint32_t part,
+                  const uint8_t *input,
+                  size_t input_len,
+                  char **output);
+
+/**
+ * Release a string returned by `aoc2016_solve`.  Passing null does nothing.
+ *
+ * # Safety
+ *
+ * `s` must be null or a string from `aoc2016_solve` which has not already been freed.
+ */
+void aoc2016_free_string(char *s);
+
+#ifdef __cplusplus
+} // extern "C"
+#endif // __cplusplus
+
+#endif /* AOC2016_H */
@@ -0,0 +1,147 @@
+// C ABI for the solvers, so that they can be called from other languages.  The header in
+// include/ is generated by cbindgen; after changing the ABI, regenerate it from this directory with
+//
+//     cbindgen --config cbindgen.toml --output include/aoc2016.h
+//
+// The test checks that it's up to date.
+
+use std::ffi::{c_char, c_int, CString};
+use std::panic;
+
+use adventofcode_2016::{input, untrusted_implementation};
+
+pub const AOC2016_OK: c_int = 0;
+pub const AOC2016_ERR_NULL_POINTER: c_int = 1;
+pub const AOC2016_ERR_INVALID_UTF8: c_int = 2;
+pub const AOC2016_ERR_UNKNOWN_DAY: c_int = 3;
+pub const AOC2016_ERR_UNKNOWN_PART: c_int = 4;
+pub const AOC2016_ERR_SOLVER_PANICKED: c_int = 5;
+pub const AOC2016_ERR_INVALID_INPUT: c_int = 6;
+
+/// Solve one part of a day's puzzle.
+///
+/// `input` points to `input_len` bytes of UTF-8 puzzle input.  On return `*output` is set to a
+/// newly allocated NUL-terminated string: the answer if the result is `AOC2016_OK`, otherwise a
+/// description of the error.  It must be released with `aoc2016_free_string`.
+///
+/// # Safety
+///
+/// `input` must be valid for reads of `input_len` bytes and `output` must be valid for a write.
+#[no_mangle]
+pub unsafe extern "C" fn aoc2016_solve(
+    day: u32,
+    part: u32,
+    input: *const u8,
+    input_len: usize,
+    output: *mut *mut c_char,
+) -> c_int {
+    if output.is_null() {
+        return AOC2016_ERR_NULL_POINTER;
+    }
+    let (code, message) = if input.is_null() {
+        (AOC2016_ERR_NULL_POINTER, "input is null".to_string())
+    } else {
+        match std::str::from_utf8(std::slice::from_raw_parts(input, input_len)) {
+            Ok(input) => solve(day as usize, part, input),
+            Err(e) => (AOC2016_ERR_INVALID_UTF8, e.to_string()),
+        }
+    };
+    *output = CString::new(message.replace('\0', "")).unwrap().into_raw();
+    code
+}
+
+/// Release a string returned by `aoc2016_solve`.  Passing null does nothing.
+///
+/// # Safety
+///
+/// `s` must be null or a string from `aoc2016_solve` which has not already been freed.
+#[no_mangle]
+pub unsafe extern "C" fn aoc2016_free_string(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+fn solve(day: usize, part: u32, input: &str) -> (c_int, String) {
+    // the caller's input might ask for anything, so use a solver which copes with that
+    let new = match untrusted_implementation(day) {
+        Some(new) => new,
+        None => {
+            return (
+                AOC2016_ERR_UNKNOWN_DAY,
+                format!("day {} is not available", day),
+            )
+        }
+    };
+    if part != 1 && part != 2 {
+        return (AOC2016_ERR_UNKNOWN_PART, format!("no such part: {}", part));
+    }
+    // unwinding into the caller is undefined behaviour, so catch solvers panicking, whether on
+    // input they reject or because of a bug
+    match panic::catch_unwind(|| {
+        let solver = new(input);
+        if part == 1 {
+            solver.part1()
+        } else {
+            solver.part2()
+        }
+    }) {
+        Ok(answer) => (AOC2016_OK, answer),
+        Err(payload) if input::is_invalid(payload.as_ref()) => (
+            AOC2016_ERR_INVALID_INPUT,
+            input::panic_message(payload.as_ref()),
+        ),
+        Err(payload) => (
+            AOC2016_ERR_SOLVER_PANICKED,
+            input::panic_message(payload.as_ref()),
+        ),
+    }
+}
+
+#[test]
+fn test() {
+    use std::ffi::CStr;
+    use std::ptr;
+
+    let call = |day, part, input: &[u8]| unsafe {
+        let mut output = ptr::null_mut();
+        let code = aoc2016_solve(day, part, input.as_ptr(), input.len(), &mut output);
+        let message = CStr::from_ptr(output).to_str().unwrap().to_string();
+        aoc2016_free_string(output);
+        (code, message)
+    };
+
+    assert_eq!((AOC2016_OK, "4".to_string()), call(1, 2, b"R8, R4, R4, R8"));
+    // walking billions of blocks takes no longer than walking a few
+    let huge = ["R2000000000"; 5].join(", ");
+    assert_eq!((AOC2016_OK, "1".to_string()), call(1, 2, huge.as_bytes()));
+    assert_eq!(AOC2016_ERR_UNKNOWN_DAY, call(26, 1, b"").0);
+    assert_eq!(AOC2016_ERR_UNKNOWN_PART, call(1, 3, b"R1").0);
+    assert_eq!(AOC2016_ERR_INVALID_UTF8, call(1, 1, b"\xff").0);
+    assert_eq!(
+        (
+            AOC2016_ERR_INVALID_INPUT,
+            "invalid input: invalid turn: \"X1\"".to_string()
+        ),
+        call(1, 1, b"X1")
+    );
+
+    unsafe {
+        assert_eq!(
+            AOC2016_ERR_NULL_POINTER,
+            aoc2016_solve(1, 1, b"R1".as_ptr(), 2, ptr::null_mut())
+        );
+        aoc2016_free_string(ptr::null_mut());
+    }
+
+    let dir = env!("CARGO_MANIFEST_DIR");
+    let mut header = vec![];
+    cbindgen::generate_with_config(dir, cbindgen::Config::from_file("cbindgen.toml").unwrap())
+        .unwrap()
+        .write(&mut header);
+    assert!(
+        String::from_utf8(header).unwrap()
+            == std::fs::read_to_string(format!("{}/include/aoc2016.h", dir)).unwrap(),
+        "include/aoc2016.h is out of date; regenerate it with cbindgen"
+    );
+}
@@ -31,7 +31,7 @@ pub struct Solver {
     // input: Vec<i32>
 }
 
-impl Puzzle for Solver {
+impl Puzzle<'_> for Solver {
     fn new(input: &str) -> Self {
         Self {
             // input: parse_input(input),
@@ -0,0 +1,201 @@
+// HTTP API for the solvers, listening on localhost only.
+//
+//     POST /2016/day/{n}   body: puzzle input
+//
+// responds with JSON containing both answers and how long each part took.  Requests with inputs
+// larger than MAX_INPUT_SIZE are rejected, and solvers which take longer than TIME_LIMIT are
+// abandoned (their thread is left to finish in the background, since solvers can't be cancelled).
+// Once MAX_ABANDONED solvers are still running, further requests are refused until one finishes.
+
+use std::any::Any;
+use std::io::Read;
+use std::panic;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use adventofcode_2016::trace::Value;
+use adventofcode_2016::{input, untrusted_implementation, Constructor};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+const DEFAULT_PORT: u16 = 2016;
+const MAX_INPUT_SIZE: usize = 1024 * 1024;
+const TIME_LIMIT: Duration = Duration::from_secs(30);
+const MAX_ABANDONED: usize = 4;
+
+// solver threads which haven't finished yet; requests are handled one at a time, so all but the
+// current request's have been abandoned
+static RUNNING: AtomicUsize = AtomicUsize::new(0);
+
+fn main() {
+    let port = std::env::args()
+        .nth(1)
+        .map_or(DEFAULT_PORT, |port| port.parse().unwrap());
+    let server = Server::http(("127.0.0.1", port)).unwrap();
+    eprintln!("Listening on http://127.0.0.1:{}", port);
+
+    for request in server.incoming_requests() {
+        handle(request);
+    }
+}
+
+fn handle(mut request: Request) {
+    let (status, body) = match respond(&mut request) {
+        Ok(body) => (200, body),
+        Err((status, message)) => (status, error_json(&message)),
+    };
+    eprintln!("{} {} {}", request.method(), request.url(), status);
+    let response = Response::from_string(body)
+        .with_status_code(status)
+        .with_header(Header::from_bytes("Content-Type", "application/json").unwrap());
+    if let Err(e) = request.respond(response) {
+        eprintln!("Failed to send response: {}", e);
+    }
+}
+
+fn respond(request: &mut Request) -> Result<String, (u16, String)> {
+    let day = parse_route(request.url()).ok_or((404, "Not found".to_string()))?;
+    if *request.method() != Method::Post {
+        return Err((405, "Method not allowed".to_string()));
+    }
+    let new = untrusted_implementation(day)
+        .ok_or_else(|| (404, format!("Day {} is not available", day)))?;
+
+    if request.body_length().unwrap_or(0) > MAX_INPUT_SIZE {
+        return Err((413, "Input too large".to_string()));
+    }
+    let mut body = vec![];
+    request
+        .as_reader()
+        .take(MAX_INPUT_SIZE as u64 + 1)
+        .read_to_end(&mut body)
+        .map_err(|e| (400, e.to_string()))?;
+    if body.len() > MAX_INPUT_SIZE {
+        return Err((413, "Input too large".to_string()));
+    }
+    let input = String::from_utf8(body).map_err(|_| (400, "Input is not UTF-8".to_string()))?;
+    run(new, input, TIME_LIMIT).map(|answers| answers_json(day, &answers))
+}
+
+// Solve on a thread of its own, so that it can be abandoned after the time limit.  Solvers panic on
+// input they can't parse, so report that as the input's fault; any other panic is a bug in the
+// solver.
+fn run(
+    new: Constructor,
+    input: String,
+    time_limit: Duration,
+) -> Result<[(String, f64); 2], (u16, String)> {
+    if RUNNING.load(Ordering::SeqCst) >= MAX_ABANDONED {
+        return Err((503, "Too many solvers still running".to_string()));
+    }
+    RUNNING.fetch_add(1, Ordering::SeqCst);
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let _ = tx.send(panic::catch_unwind(|| solve(new, &input)));
+        RUNNING.fetch_sub(1, Ordering::SeqCst);
+    });
+    match rx.recv_timeout(time_limit) {
+        Ok(Ok(answers)) => Ok(answers),
+        Ok(Err(payload)) => Err(panic_error(payload.as_ref())),
+        Err(mpsc::RecvTimeoutError::Timeout) => Err((504, "Time limit exceeded".to_string())),
+        Err(mpsc::RecvTimeoutError::Disconnected) => Err((500, "Solver failed".to_string())),
+    }
+}
+
+fn panic_error(payload: &(dyn Any + Send)) -> (u16, String) {
+    let status = if input::is_invalid(payload) { 422 } else { 500 };
+    (status, input::panic_message(payload))
+}
+
+// answer and elapsed time of each part; part 1 includes parsing the input
+fn solve(new: Constructor, input: &str) -> [(String, f64); 2] {
+    let t0 = Instant::now();
+    let solver = new(input);
+    let part1 = solver.part1();
+    let t1 = t0.elapsed().as_secs_f64();
+    let t0 = Instant::now();
+    let part2 = solver.part2();
+    [(part1, t1), (part2, t0.elapsed().as_secs_f64())]
+}
+
+// the day number from a path like /2016/day/7
+fn parse_route(url: &str) -> Option<usize> {
+    let path = url.split('?').next().unwrap();
+    path.strip_prefix("/2016/day/")?.parse().ok()
+}
+
+fn answers_json(day: usize, answers: &[(String, f64); 2]) -> String {
+    let mut json = format!("{{\"day\":{}", day);
+    for (part, (answer, elapsed)) in answers.iter().enumerate() {
+        json += &format!(",\"part{}\":{{\"answer\":", part + 1);
+        answer.write_json(&mut json);
+        json += &format!(",\"seconds\":{}}}", elapsed);
+    }
+    json.push('}');
+    json
+}
+
+fn error_json(message: &str) -> String {
+    let mut json = String::from("{\"error\":");
+    message.write_json(&mut json);
+    json.push('}');
+    json
+}
+
+#[test]
+fn test() {
+    assert_eq!(Some(7), parse_route("/2016/day/7"));
+    assert_eq!(Some(12), parse_route("/2016/day/12?part=1"));
+    assert_eq!(None, parse_route("/2015/day/7"));
+    assert_eq!(None, parse_route("/2016/day/x"));
+
+    assert_eq!(
+        r##"{"day":8,"part1":{"answer":"6","seconds":0.5},"part2":{"answer":"#\n#","seconds":0.25}}"##,
+        answers_json(8, &[("6".to_string(), 0.5), ("#\n#".to_string(), 0.25)])
+    );
+    assert_eq!(r#"{"error":"Not found"}"#, error_json("Not found"));
+
+    let error = |f: fn()| panic_error(panic::catch_unwind(f).unwrap_err().as_ref());
+    assert_eq!(
+        (422, "invalid input: bad".to_string()),
+        error(|| input::invalid("bad"))
+    );
+    assert_eq!((500, "oops".to_string()), error(|| panic!("oops")));
+
+    // day 1 is served by the solver which doesn't step through every block
+    #[cfg(feature = "day1")]
+    {
+        let new = untrusted_implementation(1).unwrap();
+        let input = ["R2000000000"; 5].join(", ");
+        let answers = run(new, input, TIME_LIMIT).unwrap();
+        assert_eq!(
+            ("2000000000", "1"),
+            (answers[0].0.as_str(), answers[1].0.as_str())
+        );
+    }
+
+    // once enough solvers have been abandoned, requests are refused until one finishes
+    use adventofcode_2016::Puzzle;
+    struct Slow;
+    impl Puzzle<'_> for Slow {
+        fn new(_: &str) -> Self {
+            thread::sleep(Duration::from_millis(500));
+            Slow
+        }
+        fn part1(&self) -> String {
+            String::new()
+        }
+        fn part2(&self) -> String {
+            String::new()
+        }
+    }
+    let slow: Constructor = |input| Box::new(Slow::new(input));
+    let time_limit = Duration::from_millis(10);
+    for _ in 0..MAX_ABANDONED {
+        assert_eq!(504, run(slow, String::new(), time_limit).unwrap_err().0);
+    }
+    assert_eq!(503, run(slow, String::new(), time_limit).unwrap_err().0);
+    thread::sleep(Duration::from_secs(1));
+    assert!(run(slow, String::new(), Duration::from_secs(1)).is_ok());
+}
@@ -1,12 +1,16 @@
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
 
+use crate::generate::Rng;
+use crate::input;
+use crate::trace;
 use crate::Puzzle;
 
 pub struct Solver {
     instructions: Vec<Instruction>,
 }
 
-impl Puzzle for Solver {
+impl Puzzle<'_> for Solver {
     fn new(input: &str) -> Self {
         Self {
             instructions: parse_input(input),
@@ -14,94 +18,620 @@ impl Puzzle for Solver {
     }
 
     fn part1(&self) -> String {
-        part1(&self.instructions).to_string()
+        part1(&self.instructions, Geometry::Square).to_string()
     }
 
     fn part2(&self) -> String {
-        part2(&self.instructions).to_string()
+        part2(&self.instructions, Geometry::Square)
+            .unwrap_or_else(|| input::invalid("no location is visited twice"))
+            .to_string()
     }
 }
 
-fn part1(input: &[Instruction]) -> u32 {
-    let mut state = State::new();
+// Alternative solver which finds the first revisit by intersecting whole segments of the walk
+// rather than stepping one block at a time, so that its time and memory depend on the number of
+// instructions rather than how far they walk.
+pub struct SegmentSolver {
+    instructions: Vec<Instruction>,
+}
+
+impl Puzzle<'_> for SegmentSolver {
+    fn new(input: &str) -> Self {
+        Self {
+            instructions: parse_input(input),
+        }
+    }
+
+    fn part1(&self) -> String {
+        part1(&self.instructions, Geometry::Square).to_string()
+    }
+
+    fn part2(&self) -> String {
+        part2_segments(&self.instructions)
+            .unwrap_or_else(|| input::invalid("no location is visited twice"))
+            .to_string()
+    }
+}
+
+// Both answers for the instructions walked on any grid: the distance to the end of the walk, and
+// to the first block visited twice (if there is one).
+pub fn solve(input: &str, geometry: Geometry) -> (u32, Option<u32>) {
+    let instructions = parse_input(input);
+    (
+        part1(&instructions, geometry),
+        part2(&instructions, geometry),
+    )
+}
+
+fn part1(input: &[Instruction], geometry: Geometry) -> u32 {
+    let mut state = State::new(geometry);
     for instruction in input {
-        state.turn(instruction.turn);
+        state.turn(&instruction.turns);
         state.walk(instruction.walk);
+        state.trace(1);
     }
     state.distance_from_origin()
 }
 
-fn part2(input: &[Instruction]) -> u32 {
-    let mut state = State::new();
+fn part2(input: &[Instruction], geometry: Geometry) -> Option<u32> {
+    let mut state = State::new(geometry);
     let mut visited: HashSet<(i32, i32)> = HashSet::new();
     for instruction in input {
-        state.turn(instruction.turn);
+        state.turn(&instruction.turns);
         for _ in 0..instruction.walk {
             state.walk(1);
+            state.trace(2);
             if visited.contains(&state.position) {
-                return state.distance_from_origin();
+                return Some(state.distance_from_origin());
             }
             visited.insert(state.position);
         }
     }
-    panic!("Didn't visit any location twice")
+    None
 }
 
-#[derive(Clone, Copy)]
-enum Direction {
+// Segments are axis-aligned, so this only works on the square grid.
+fn part2_segments(input: &[Instruction]) -> Option<u32> {
+    let mut state = State::new(Geometry::Square);
+    let mut walked = SegmentIndex::default();
+    for instruction in input {
+        state.turn(&instruction.turns);
+        let start = state.position;
+        state.walk(instruction.walk);
+        if instruction.walk == 0 {
+            continue;
+        }
+        let segment = Segment::new(start, state.position);
+        if let Some(revisit) = walked
+            .crossing(&segment)
+            .filter_map(|other| segment.first_intersection(other))
+            .min_by_key(|&(x, y)| (x - start.0).abs() + (y - start.1).abs())
+        {
+            return Some(revisit.0.unsigned_abs() + revisit.1.unsigned_abs());
+        }
+        walked.insert(segment);
+    }
+    None
+}
+
+// The shortest list of L/R instructions leading from the start to the target: a walk east or west
+// and then one north or south.  Every instruction turns, so heading straight north or south needs
+// a walk of zero first, and the start itself is reached by a single walk of zero.  The route never
+// crosses itself, so it doesn't revisit any block.  Like any position, the target has to be within
+// ±i32::MAX.
+pub fn route(target: (i32, i32)) -> String {
+    assert!(
+        target.0 != i32::MIN && target.1 != i32::MIN,
+        "no route to {:?}",
+        target
+    );
+    let mut state = State::new(Geometry::Square);
+    let legs = match target {
+        (x, 0) => vec![(x, 0)],
+        (x, y) => vec![(x, 0), (0, y)],
+    };
+    let mut instructions = vec![];
+    for (dx, dy) in legs {
+        // turn to face along the leg; either way will do for a walk of zero
+        let (turn, letter) = [(Turn::Right, 'R'), (Turn::Left, 'L')]
+            .into_iter()
+            .find(|&(turn, _)| {
+                let (x, y) = Geometry::Square.step(state.direction.turn(turn, Geometry::Square));
+                x * dx + y * dy >= 0
+            })
+            .unwrap();
+        let walk = dx.unsigned_abs() + dy.unsigned_abs();
+        state.turn(&[turn]);
+        state.walk(walk);
+        instructions.push(format!("{}{}", letter, walk));
+    }
+    debug_assert_eq!(target, state.position);
+    instructions.join(", ")
+}
+
+// Every block stepped on while following the instructions, in order.  The starting block is step 0,
+// and counts as a visit in revisits(), but not for the first revisit, which is the puzzle's answer.
+pub struct Path {
+    pub steps: Vec<(i32, i32)>,
+}
+
+// A block which the path steps on more than once, and the index of each step which lands on it.
+#[derive(Debug, PartialEq)]
+pub struct Revisit {
+    pub position: (i32, i32),
+    pub steps: Vec<usize>,
+}
+
+impl Revisit {
+    pub fn visits(&self) -> usize {
+        self.steps.len()
+    }
+}
+
+pub fn path(input: &str, geometry: Geometry) -> Path {
+    let mut state = State::new(geometry);
+    let mut steps = vec![state.position];
+    for instruction in parse_input(input) {
+        state.turn(&instruction.turns);
+        for _ in 0..instruction.walk {
+            state.walk(1);
+            steps.push(state.position);
+        }
+    }
+    Path { steps }
+}
+
+impl Path {
+    // every block visited more than once, in the order they were first revisited
+    pub fn revisits(&self) -> Vec<Revisit> {
+        let mut visits: HashMap<(i32, i32), Vec<usize>> = HashMap::new();
+        for (step, &position) in self.steps.iter().enumerate() {
+            visits.entry(position).or_default().push(step);
+        }
+        let mut revisits: Vec<Revisit> = visits
+            .into_iter()
+            .filter(|(_, steps)| steps.len() > 1)
+            .map(|(position, steps)| Revisit { position, steps })
+            .collect();
+        revisits.sort_by_key(|revisit| revisit.steps[1]);
+        revisits
+    }
+
+    // the first block stepped on for a second time, as in part 2, if any
+    pub fn first_revisit(&self) -> Option<(i32, i32)> {
+        let mut visited = HashSet::new();
+        self.steps[1..]
+            .iter()
+            .find(|&&position| !visited.insert(position))
+            .copied()
+    }
+}
+
+// Steps backwards and forwards through the walk one block or one instruction at a time, for
+// seeing how the answers come about.
+pub struct Stepper {
+    instructions: Vec<String>,
+    snapshots: Vec<Snapshot>,
+    current: usize,
+}
+
+// Where the walk has got to after a block is stepped on, or after the turn of an instruction
+// which doesn't walk anywhere.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Snapshot {
+    // how many instructions have been started, so 0 before the first
+    pub instruction: usize,
+    pub heading: Direction,
+    pub position: (i32, i32),
+    // how many times the current block has been stepped on, and how many different blocks have
+    pub visits: usize,
+    pub visited: usize,
+}
+
+impl Stepper {
+    pub fn new(input: &str) -> Self {
+        let mut state = State::new(Geometry::Square);
+        let mut visits = HashMap::from([(state.position, 1)]);
+        let snapshot = |instruction, state: &State, visits: &HashMap<(i32, i32), usize>| Snapshot {
+            instruction,
+            heading: state.direction,
+            position: state.position,
+            visits: visits[&state.position],
+            visited: visits.len(),
+        };
+
+        let mut snapshots = vec![snapshot(0, &state, &visits)];
+        for (i, instruction) in parse_input(input).iter().enumerate() {
+            state.turn(&instruction.turns);
+            if instruction.walk == 0 {
+                snapshots.push(snapshot(i + 1, &state, &visits));
+            }
+            for _ in 0..instruction.walk {
+                state.walk(1);
+                *visits.entry(state.position).or_default() += 1;
+                snapshots.push(snapshot(i + 1, &state, &visits));
+            }
+        }
+        Self {
+            instructions: input::comma_list(input).map(String::from).collect(),
+            snapshots,
+            current: 0,
+        }
+    }
+
+    pub fn current(&self) -> &Snapshot {
+        &self.snapshots[self.current]
+    }
+
+    // Each of these moves returns false, without moving, if there's nowhere to go.
+
+    pub fn next_block(&mut self) -> bool {
+        self.move_to((self.current + 1 < self.snapshots.len()).then_some(self.current + 1))
+    }
+
+    pub fn previous_block(&mut self) -> bool {
+        self.move_to(self.current.checked_sub(1))
+    }
+
+    // to the end of this instruction, or of the next if already at the end of this one
+    pub fn next_instruction(&mut self) -> bool {
+        self.move_to((self.current + 1..self.snapshots.len()).find(|&i| self.ends_instruction(i)))
+    }
+
+    // to the end of the previous instruction
+    pub fn previous_instruction(&mut self) -> bool {
+        self.move_to((0..self.current).rev().find(|&i| self.ends_instruction(i)))
+    }
+
+    // to the first time a block is stepped on for the second time, which is the answer to part 2;
+    // as there, starting on a block doesn't count as stepping on it
+    pub fn first_revisit(&mut self) -> bool {
+        let start = self.snapshots[0].position;
+        self.move_to(
+            self.snapshots.iter().position(|snapshot| {
+                snapshot.visits - usize::from(snapshot.position == start) == 2
+            }),
+        )
+    }
+
+    pub fn start(&mut self) {
+        self.current = 0;
+    }
+
+    pub fn end(&mut self) {
+        self.current = self.snapshots.len() - 1;
+    }
+
+    fn ends_instruction(&self, i: usize) -> bool {
+        self.snapshots
+            .get(i + 1)
+            .is_none_or(|next| next.instruction != self.snapshots[i].instruction)
+    }
+
+    fn move_to(&mut self, i: Option<usize>) -> bool {
+        if let Some(i) = i {
+            self.current = i;
+        }
+        i.is_some()
+    }
+}
+
+impl fmt::Display for Stepper {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let snapshot = self.current();
+        match snapshot.instruction {
+            0 => write!(f, "start      ")?,
+            i => write!(
+                f,
+                "{:<5} {:>5}",
+                self.instructions[i - 1],
+                format!("#{}", i)
+            )?,
+        }
+        write!(
+            f,
+            "  block {}/{}  heading {:?}  position {:?}  distance {}  visits {}  visited {}",
+            self.current,
+            self.snapshots.len() - 1,
+            snapshot.heading,
+            snapshot.position,
+            Geometry::Square.distance(snapshot.position),
+            snapshot.visits,
+            snapshot.visited
+        )
+    }
+}
+
+// Drawing the path, with the start, the end and the first revisit marked.
+impl Path {
+    // the blocks where the path starts, turns or ends, enough to draw it with straight lines
+    fn corners(&self) -> Vec<(i32, i32)> {
+        let direction = |a: (i32, i32), b: (i32, i32)| (b.0 - a.0, b.1 - a.1);
+        let mut corners = vec![self.steps[0]];
+        for step in self.steps.windows(3) {
+            if direction(step[0], step[1]) != direction(step[1], step[2]) {
+                corners.push(step[1]);
+            }
+        }
+        if self.steps.len() > 1 {
+            corners.push(self.steps[self.steps.len() - 1]);
+        }
+        corners
+    }
+
+    // the smallest and largest x and y
+    fn bounds(&self) -> ((i32, i32), (i32, i32)) {
+        self.steps
+            .iter()
+            .fold((self.steps[0], self.steps[0]), |(min, max), &(x, y)| {
+                ((min.0.min(x), min.1.min(y)), (max.0.max(x), max.1.max(y)))
+            })
+    }
+
+    // An SVG image of the path.  North is up, so y is negated.
+    pub fn svg(&self) -> String {
+        let (min, max) = self.bounds();
+        let size = (max.0 - min.0).max(max.1 - min.1).max(1) as f64;
+        let margin = size / 20.0;
+        let radius = size / 80.0;
+        let points: Vec<String> = self
+            .corners()
+            .iter()
+            .map(|(x, y)| format!("{},{}", x, -y))
+            .collect();
+        let marker = |(x, y): (i32, i32), colour: &str| {
+            format!(
+                "<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"{}\"/>\n",
+                x, -y, radius, colour
+            )
+        };
+
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"{} {} {} {}\">\n",
+            min.0 as f64 - margin,
+            -max.1 as f64 - margin,
+            (max.0 - min.0) as f64 + 2.0 * margin,
+            (max.1 - min.1) as f64 + 2.0 * margin
+        );
+        svg += &format!(
+            "<polyline points=\"{}\" fill=\"none\" stroke=\"black\" \
+             stroke-width=\"1.5\" vector-effect=\"non-scaling-stroke\"/>\n",
+            points.join(" ")
+        );
+        svg += &marker(self.steps[0], "green");
+        svg += &marker(self.steps[self.steps.len() - 1], "red");
+        if let Some(revisit) = self.first_revisit() {
+            svg += &marker(revisit, "blue");
+        }
+        svg + "</svg>\n"
+    }
+
+    // A map of the path scaled down to fit within width by height characters, with the start
+    // marked S, the end E and the first revisit X, which is drawn over the others.
+    pub fn ascii(&self, width: usize, height: usize) -> String {
+        let (min, max) = self.bounds();
+        let span = ((max.0 - min.0) as i64 + 1, (max.1 - min.1) as i64 + 1);
+        let width = span.0.min(width.max(1) as i64);
+        let height = span.1.min(height.max(1) as i64);
+        let cell = |(x, y): (i32, i32)| {
+            (
+                ((max.1 - y) as i64 * height / span.1) as usize,
+                ((x - min.0) as i64 * width / span.0) as usize,
+            )
+        };
+
+        let mut map = vec![vec![' '; width as usize]; height as usize];
+        for &step in self.steps.iter() {
+            let (row, col) = cell(step);
+            map[row][col] = '#';
+        }
+        let mut marks = vec![
+            (self.steps[self.steps.len() - 1], 'E'),
+            (self.steps[0], 'S'),
+        ];
+        if let Some(revisit) = self.first_revisit() {
+            marks.push((revisit, 'X'));
+        }
+        for (position, mark) in marks {
+            let (row, col) = cell(position);
+            map[row][col] = mark;
+        }
+        map.iter()
+            .map(|row| row.iter().collect::<String>().trim_end().to_string() + "\n")
+            .collect()
+    }
+}
+
+// Walked segments indexed by the row or column they lie along, so that a new segment only needs to
+// be checked against those on the rows and columns it spans rather than the whole walk so far.
+// Segments of a single block are filed as rows.
+#[derive(Default)]
+struct SegmentIndex {
+    rows: BTreeMap<i32, Vec<Segment>>,
+    columns: BTreeMap<i32, Vec<Segment>>,
+}
+
+impl SegmentIndex {
+    fn insert(&mut self, segment: Segment) {
+        if segment.min.1 == segment.max.1 {
+            self.rows.entry(segment.min.1).or_default().push(segment);
+        } else {
+            self.columns.entry(segment.min.0).or_default().push(segment);
+        }
+    }
+
+    // the segments which might share a block with the given one
+    fn crossing<'a>(&'a self, segment: &Segment) -> impl Iterator<Item = &'a Segment> {
+        self.rows
+            .range(segment.min.1..=segment.max.1)
+            .chain(self.columns.range(segment.min.0..=segment.max.0))
+            .flat_map(|(_, segments)| segments)
+    }
+}
+
+// The blocks covered by a single straight walk, not including the block it started from.  Since
+// segments are axis-aligned they're stored as an inclusive bounding box.
+struct Segment {
+    start: (i32, i32),
+    min: (i32, i32),
+    max: (i32, i32),
+}
+
+impl Segment {
+    fn new(start: (i32, i32), end: (i32, i32)) -> Self {
+        // step one block away from the start towards the end
+        let first = (
+            start.0 + (end.0 - start.0).signum(),
+            start.1 + (end.1 - start.1).signum(),
+        );
+        Self {
+            start,
+            min: (first.0.min(end.0), first.1.min(end.1)),
+            max: (first.0.max(end.0), first.1.max(end.1)),
+        }
+    }
+
+    // the block covered by both segments which is closest to the start of this one
+    fn first_intersection(&self, other: &Segment) -> Option<(i32, i32)> {
+        let min = (self.min.0.max(other.min.0), self.min.1.max(other.min.1));
+        let max = (self.max.0.min(other.max.0), self.max.1.min(other.max.1));
+        if min.0 > max.0 || min.1 > max.1 {
+            return None;
+        }
+        Some((
+            self.start.0.clamp(min.0, max.0),
+            self.start.1.clamp(min.1, max.1),
+        ))
+    }
+}
+
+// The grid being walked.  The puzzle is on a square grid; the others allow diagonal steps
+// (Octagonal) or use hexagons with flat tops, where north and south are neighbours but east and
+// west aren't.  Hex positions are axial coordinates, with x along the north-east axis.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Geometry {
+    Square,
+    Octagonal,
+    Hex,
+}
+
+impl Geometry {
+    // the headings that can be faced, clockwise from north; each turn moves one place along
+    fn headings(self) -> &'static [Direction] {
+        use Direction::*;
+        match self {
+            Geometry::Square => &[North, East, South, West],
+            Geometry::Octagonal => &[
+                North, NorthEast, East, SouthEast, South, SouthWest, West, NorthWest,
+            ],
+            Geometry::Hex => &[North, NorthEast, SouthEast, South, SouthWest, NorthWest],
+        }
+    }
+
+    // the change in position from a single step in the given direction
+    fn step(self, direction: Direction) -> (i32, i32) {
+        match (self, direction) {
+            (_, Direction::North) => (0, 1),
+            (_, Direction::South) => (0, -1),
+            (_, Direction::East) => (1, 0),
+            (_, Direction::West) => (-1, 0),
+            (Geometry::Hex, Direction::NorthEast) => (1, 0),
+            (Geometry::Hex, Direction::SouthWest) => (-1, 0),
+            (_, Direction::NorthEast) => (1, 1),
+            (_, Direction::SouthEast) => (1, -1),
+            (_, Direction::SouthWest) => (-1, -1),
+            (_, Direction::NorthWest) => (-1, 1),
+        }
+    }
+
+    // the fewest steps from the origin to a position: Manhattan distance on the square grid,
+    // Chebyshev distance when diagonal steps are allowed, and hex distance on the hex grid
+    pub fn distance(self, (x, y): (i32, i32)) -> u32 {
+        match self {
+            Geometry::Square => x.unsigned_abs() + y.unsigned_abs(),
+            Geometry::Octagonal => x.unsigned_abs().max(y.unsigned_abs()),
+            Geometry::Hex => {
+                let (x, y) = (x as i64, y as i64);
+                ((x.abs() + y.abs() + (x + y).abs()) / 2) as u32
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Direction {
     North,
+    NorthEast,
     East,
+    SouthEast,
     South,
+    SouthWest,
     West,
+    NorthWest,
 }
 
 impl Direction {
-    fn turn(&self, turn: Turn) -> Direction {
-        match turn {
-            Turn::Left => match self {
-                Direction::North => Direction::West,
-                Direction::East => Direction::North,
-                Direction::South => Direction::East,
-                Direction::West => Direction::South,
-            },
-            Turn::Right => match self {
-                Direction::North => Direction::East,
-                Direction::East => Direction::South,
-                Direction::South => Direction::West,
-                Direction::West => Direction::North,
-            },
-        }
+    fn turn(self, turn: Turn, geometry: Geometry) -> Direction {
+        let headings = geometry.headings();
+        let n = headings.len();
+        let i = headings.iter().position(|&d| d == self).unwrap();
+        let by = match turn {
+            Turn::Left => n - 1,
+            Turn::Right => 1,
+            Turn::Around => n / 2,
+            Turn::Straight => 0,
+        };
+        headings[(i + by) % n]
     }
 }
 
 struct State {
+    geometry: Geometry,
     direction: Direction,
     position: (i32, i32),
 }
 
 impl State {
-    fn new() -> Self {
+    fn new(geometry: Geometry) -> Self {
         Self {
+            geometry,
             direction: Direction::North,
             position: (0, 0),
         }
     }
 
-    fn turn(&mut self, turn: Turn) {
-        self.direction = self.direction.turn(turn);
+    fn turn(&mut self, turns: &[Turn]) {
+        for &turn in turns {
+            self.direction = self.direction.turn(turn, self.geometry);
+        }
     }
 
+    // Positions are kept within ±i32::MAX, so that distances fit in a u32; walking any further
+    // rejects the input.
     fn walk(&mut self, walk: u32) {
-        match self.direction {
-            Direction::North => self.position.1 += walk as i32,
-            Direction::East => self.position.0 += walk as i32,
-            Direction::South => self.position.1 -= walk as i32,
-            Direction::West => self.position.0 -= walk as i32,
+        let (dx, dy) = self.geometry.step(self.direction);
+        let walked = |from: i32, d: i32| {
+            i32::try_from(walk)
+                .ok()
+                .and_then(|walk| from.checked_add(d * walk))
+                .filter(|&to| to != i32::MIN)
+                .unwrap_or_else(|| input::invalid("the walk goes too far from the start"))
         };
+        self.position = (walked(self.position.0, dx), walked(self.position.1, dy));
     }
 
     fn distance_from_origin(&self) -> u32 {
-        self.position.0.unsigned_abs() + self.position.1.unsigned_abs()
+        self.geometry.distance(self.position)
+    }
+
+    fn trace(&self, part: u32) {
+        trace::event!(
+            "day1.position",
+            part = part,
+            x = self.position.0,
+            y = self.position.1,
+            heading = format!("{:?}", self.direction),
+        );
     }
 }
 
@@ -109,33 +639,284 @@ impl State {
 enum Turn {
     Left,
     Right,
+    // U: turn to face the opposite way
+    Around,
+    // F: keep going the same way
+    Straight,
 }
 
+// One or more turns, taken in order, followed by a walk: the puzzle only uses L or R, but
+// something like "LL4" or "U2" is also accepted.
 struct Instruction {
-    turn: Turn,
+    turns: Vec<Turn>,
     walk: u32,
 }
 
 fn parse_input(input: &str) -> Vec<Instruction> {
-    input
-        .trim_end_matches('\n')
-        .split(", ")
-        .map(|instruction| Instruction {
-            turn: match instruction.chars().next().unwrap() {
-                'L' => Turn::Left,
-                'R' => Turn::Right,
-                _ => panic!("Invalid turn"),
-            },
-            walk: instruction[1..].parse().unwrap(),
+    input::comma_list(input)
+        .map(|instruction| {
+            let distance = instruction.trim_start_matches(['L', 'R', 'U', 'F']);
+            let turns: Vec<Turn> = instruction[..instruction.len() - distance.len()]
+                .chars()
+                .map(|c| match c {
+                    'L' => Turn::Left,
+                    'R' => Turn::Right,
+                    'U' => Turn::Around,
+                    _ => Turn::Straight,
+                })
+                .collect();
+            if turns.is_empty() {
+                input::invalid(format!("invalid turn: {:?}", instruction));
+            }
+            Instruction {
+                turns,
+                walk: distance
+                    .parse()
+                    .ok()
+                    .filter(|&walk| walk <= i32::MAX as u32)
+                    .unwrap_or_else(|| {
+                        input::invalid(format!("invalid distance: {:?}", instruction))
+                    }),
+            }
         })
         .collect()
 }
 
+// Random instructions, finishing with a loop around a single block so that some location is
+// always visited twice.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut instructions: Vec<String> = (0..size.saturating_sub(4).max(1))
+        .map(|_| format!("{}{}", rng.choose(&['L', 'R']), rng.range(1, 200)))
+        .collect();
+    instructions.extend(["R1", "R1", "R1", "R1"].map(String::from));
+    instructions.join(", ") + "\n"
+}
+
 #[test]
 fn test() {
-    assert_eq!(5, part1(&parse_input("R2, L3")));
-    assert_eq!(2, part1(&parse_input("R2, R2, R2")));
-    assert_eq!(12, part1(&parse_input("R5, L5, R5, R3")));
+    assert_eq!(5, part1(&parse_input("R2, L3"), Geometry::Square));
+    assert_eq!(2, part1(&parse_input("R2, R2, R2"), Geometry::Square));
+    assert_eq!(12, part1(&parse_input("R5, L5, R5, R3"), Geometry::Square));
+
+    assert_eq!(
+        Some(4),
+        part2(&parse_input("R8, R4, R4, R8"), Geometry::Square)
+    );
+    assert_eq!(Some(4), part2_segments(&parse_input("R8, R4, R4, R8")));
+    assert_eq!(
+        Some(1),
+        part2(&parse_input("R1, R1, R1, R1, R1"), Geometry::Square)
+    );
+    assert_eq!(Some(1), part2_segments(&parse_input("R1, R1, R1, R1, R1")));
+    assert_eq!(None, part2(&parse_input("R2, L3"), Geometry::Square));
+    assert_eq!(None, part2_segments(&parse_input("R2, L3")));
+    assert_eq!(
+        Some(1_000_000),
+        part2_segments(&parse_input("R2000000, R1000000, R1000000, R2000000"))
+    );
+    assert_eq!(
+        i32::MAX as u32,
+        part1(&parse_input("R2147483647"), Geometry::Square)
+    );
+    let error = |f: fn()| {
+        let payload = std::panic::catch_unwind(f).unwrap_err();
+        payload.downcast_ref::<String>().unwrap().clone()
+    };
+    assert_eq!(
+        "invalid input: invalid distance: \"R4000000000\"",
+        error(|| {
+            parse_input("R4000000000");
+        })
+    );
+    // no single walk is too long, but together they go beyond the range of an i32
+    assert_eq!(
+        "invalid input: the walk goes too far from the start",
+        error(|| {
+            Solver::new("R2147483647, L0, R2147483647").part1();
+        })
+    );
+    assert_eq!(
+        "invalid input: the walk goes too far from the start",
+        error(|| {
+            SegmentSolver::new("R2147483647, L0, R2147483647").part2();
+        })
+    );
+    assert_eq!(
+        u32::MAX - 1,
+        part1(&parse_input("R2147483647, R2147483647"), Geometry::Square)
+    );
+    assert_eq!(u32::MAX - 1, Geometry::Hex.distance((i32::MAX, i32::MAX)));
+
+    let example = path("R8, R4, R4, R8", Geometry::Square);
+    assert_eq!(25, example.steps.len());
+    assert_eq!(Some((4, 0)), example.first_revisit());
+    assert_eq!(
+        vec![Revisit {
+            position: (4, 0),
+            steps: vec![4, 20]
+        }],
+        example.revisits()
+    );
+    // twice around a square, so the start is visited three times
+    let square = path("R2, R2, R2, R2, R2, R2, R2, R2", Geometry::Square);
+    let revisits = square.revisits();
+    assert_eq!(Some((1, 0)), square.first_revisit());
+    assert_eq!(8, revisits.len());
+    assert_eq!(vec![0, 8, 16], revisits[0].steps);
+    assert_eq!(3, revisits[0].visits());
+    assert_eq!((1, 0), revisits[1].position);
+    assert_eq!(None, path("R2, L3", Geometry::Square).first_revisit());
+    // the first revisit is the answer to part 2, for which the start doesn't count until it's
+    // stepped on again
+    for input in ["R1, R1, R1, R1, R1", "R1, R1, R1, R1", "R8, R4, R4, R8"] {
+        assert_eq!(
+            solve(input, Geometry::Square).1,
+            path(input, Geometry::Square)
+                .first_revisit()
+                .map(|position| Geometry::Square.distance(position))
+        );
+    }
+    assert_eq!(
+        Some((1, 0)),
+        path("R1, R1, R1, R1, R1", Geometry::Square).first_revisit()
+    );
+    assert!(path("R2, L3", Geometry::Square).revisits().is_empty());
+
+    assert_eq!(
+        "    E\n    #\n    #\n    #\nS###X####\n    #   #\n    #   #\n    #   #\n    #####\n",
+        example.ascii(80, 40)
+    );
+    assert_eq!("S#\n E\n", path("R10, R10", Geometry::Square).ascii(2, 2));
+    assert_eq!(
+        vec![(0, 0), (8, 0), (8, -4), (4, -4), (4, 4)],
+        example.corners()
+    );
+    let svg = example.svg();
+    assert!(svg.contains("points=\"0,0 8,0 8,4 4,4 4,-4\""));
+    assert!(svg.contains("<circle cx=\"4\" cy=\"0\" r=\"0.1\" fill=\"blue\"/>"));
+    // the revisit part 2 finds is marked, even where the path ends
+    let around = path("R1, R1, R1, R1, R1", Geometry::Square);
+    assert_eq!("SX\n##\n", around.ascii(80, 40));
+    assert!(around
+        .svg()
+        .ends_with("<circle cx=\"1\" cy=\"0\" r=\"0.0125\" fill=\"blue\"/>\n</svg>\n"));
+    let once = path("R1, R1, R1, R1", Geometry::Square);
+    assert!(!once.svg().contains("blue"));
+    assert!(!once.ascii(80, 40).contains('X'));
 
-    assert_eq!(4, part2(&parse_input("R8, R4, R4, R8")));
+    // extended instructions, and other grids
+    assert_eq!(0, part1(&parse_input("R2, U2"), Geometry::Square));
+    assert_eq!(Some(1), part2(&parse_input("R2, U1"), Geometry::Square));
+    assert_eq!(Some(1), part2_segments(&parse_input("R2, U1")));
+    assert_eq!(5, part1(&parse_input("R2, F3"), Geometry::Square));
+    assert_eq!(3, part1(&parse_input("LL3"), Geometry::Square));
+    assert_eq!((8, None), solve("R4, L4", Geometry::Octagonal));
+    assert_eq!((6, None), solve("R3, RR3", Geometry::Octagonal));
+    assert_eq!((4, None), solve("R2, R2", Geometry::Hex));
+    assert_eq!((3, None), solve("R3, RR3", Geometry::Hex));
+    assert_eq!(
+        (1, Some(1)),
+        solve("F1, R1, R1, R1, R1, R1, R1", Geometry::Hex)
+    );
+
+    let mut stepper = Stepper::new("R8, R4, R4, R8");
+    assert_eq!(0, stepper.current().instruction);
+    assert!(!stepper.previous_block());
+    assert!(stepper.next_block());
+    assert_eq!((1, 0), stepper.current().position);
+    assert!(stepper.next_instruction());
+    assert_eq!((8, 0), stepper.current().position);
+    assert!(stepper.next_instruction());
+    assert_eq!((8, -4), stepper.current().position);
+    assert_eq!(Direction::South, stepper.current().heading);
+    assert!(stepper.previous_block());
+    assert_eq!((8, -3), stepper.current().position);
+    assert!(stepper.previous_instruction());
+    assert_eq!((8, 0), stepper.current().position);
+    assert!(stepper.first_revisit());
+    assert_eq!(
+        Snapshot {
+            instruction: 4,
+            heading: Direction::North,
+            position: (4, 0),
+            visits: 2,
+            visited: 20,
+        },
+        *stepper.current()
+    );
+    assert_eq!(
+        "R8       #4  block 20/24  heading North  position (4, 0)  distance 4  visits 2  visited 20",
+        stepper.to_string()
+    );
+    stepper.end();
+    assert!(!stepper.next_instruction());
+    assert!(!stepper.next_block());
+    stepper.start();
+    assert_eq!(
+        "start        block 0/24  heading North  position (0, 0)  distance 0  visits 1  visited 1",
+        stepper.to_string()
+    );
+    // a walk of zero still turns
+    let mut stepper = Stepper::new("R0, R0");
+    assert!(stepper.next_instruction());
+    assert_eq!(Direction::East, stepper.current().heading);
+    assert!(stepper.next_block());
+    assert_eq!(Direction::South, stepper.current().heading);
+    assert!(!Stepper::new("R1, L1").first_revisit());
+    // the stepper stops at the same revisit as part 2
+    let mut stepper = Stepper::new("R1, R1, R1, R1, R1");
+    assert!(stepper.first_revisit());
+    assert_eq!((1, 0), stepper.current().position);
+    assert_eq!(5, stepper.current().instruction);
+    assert!(!Stepper::new("R1, R1, R1, R1").first_revisit());
+
+    assert_eq!("R0", route((0, 0)));
+    assert_eq!("L3", route((-3, 0)));
+    assert_eq!("R0, L5", route((0, 5)));
+    assert_eq!("R2, R3", route((2, -3)));
+    // routes lead to their target, walking no further than they need to, without revisits
+    let round_trip = |target| {
+        let route = route(target);
+        let instructions = parse_input(&route);
+        let path = path(&route, Geometry::Square);
+        assert!(instructions.len() <= 2, "{}", route);
+        assert_eq!(
+            Geometry::Square.distance(target),
+            part1(&instructions, Geometry::Square)
+        );
+        assert_eq!(Some(&target), path.steps.last());
+        assert_eq!(
+            Geometry::Square.distance(target) as usize + 1,
+            path.steps.len()
+        );
+        assert!(path.revisits().is_empty(), "{}", route);
+    };
+    round_trip((0, 0));
+    // the furthest corners are too far to step through, so just check the distance
+    for target in [(i32::MAX, -i32::MAX), (-i32::MAX, i32::MAX)] {
+        let instructions = parse_input(&route(target));
+        assert_eq!(u32::MAX - 1, part1(&instructions, Geometry::Square));
+    }
+    assert!(std::panic::catch_unwind(|| route((i32::MIN, 0))).is_err());
+    crate::generate::check(1000, |rng| {
+        let mut coordinate = || rng.range(0, 200) as i32 - 100;
+        round_trip((coordinate(), coordinate()));
+    });
+
+    // turning left then right (or right then left) leaves the direction unchanged, as does
+    // turning around twice or going straight
+    crate::generate::check(100, |rng| {
+        let geometry = rng.choose(&[Geometry::Square, Geometry::Octagonal, Geometry::Hex]);
+        let direction = rng.choose(geometry.headings());
+        let turns = |turns: &[Turn]| {
+            turns
+                .iter()
+                .fold(direction, |direction, &turn| direction.turn(turn, geometry))
+        };
+        assert_eq!(direction, turns(&[Turn::Left, Turn::Right]));
+        assert_eq!(direction, turns(&[Turn::Right, Turn::Left]));
+        assert_eq!(direction, turns(&[Turn::Around, Turn::Around]));
+        assert_eq!(direction, turns(&[Turn::Straight]));
+        assert_ne!(direction, turns(&[Turn::Around]));
+    });
 }
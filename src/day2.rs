@@ -1,10 +1,14 @@
+use std::collections::VecDeque;
+
+use crate::generate::Rng;
+use crate::input;
 use crate::Puzzle;
 
 pub struct Solver {
-    input: Vec<Vec<Direction>>,
+    input: Vec<Vec<Move>>,
 }
 
-impl Puzzle for Solver {
+impl Puzzle<'_> for Solver {
     fn new(input: &str) -> Self {
         Self {
             input: parse_input(input),
@@ -12,90 +16,208 @@ impl Puzzle for Solver {
     }
 
     fn part1(&self) -> String {
-        solve(&self.input, normal_keypad)
+        solve(&self.input, &Keypad::new(NORMAL_KEYPAD, '5'))
     }
 
     fn part2(&self) -> String {
-        solve(&self.input, diamond_keypad)
+        solve(&self.input, &Keypad::new(DIAMOND_KEYPAD, '5'))
     }
 }
 
-fn normal_keypad(pos: char, dir: Direction) -> char {
-    match (pos, dir) {
-        ('1', Direction::Down) => '4',
-        ('1', Direction::Right) => '2',
-        ('2', Direction::Down) => '5',
-        ('2', Direction::Left) => '1',
-        ('2', Direction::Right) => '3',
-        ('3', Direction::Down) => '6',
-        ('3', Direction::Left) => '2',
-        ('4', Direction::Up) => '1',
-        ('4', Direction::Down) => '7',
-        ('4', Direction::Right) => '5',
-        ('5', Direction::Up) => '2',
-        ('5', Direction::Down) => '8',
-        ('5', Direction::Left) => '4',
-        ('5', Direction::Right) => '6',
-        ('6', Direction::Up) => '3',
-        ('6', Direction::Down) => '9',
-        ('6', Direction::Left) => '5',
-        ('7', Direction::Up) => '4',
-        ('7', Direction::Right) => '8',
-        ('8', Direction::Up) => '5',
-        ('8', Direction::Left) => '7',
-        ('8', Direction::Right) => '9',
-        ('9', Direction::Up) => '6',
-        ('9', Direction::Left) => '8',
-        _ => pos,
-    }
+pub const NORMAL_KEYPAD: &str = "123\n456\n789";
+pub const DIAMOND_KEYPAD: &str = "  1\n 234\n56789\n ABC\n  D";
+
+// A keypad drawn as text, one row of keys per line with spaces where there are no keys.  Moving
+// towards a gap or off the edge of the keypad leaves the finger where it is.
+pub struct Keypad {
+    keys: Vec<Vec<Option<char>>>,
+    // row and column of the key to start on
+    start: (usize, usize),
 }
 
-fn diamond_keypad(pos: char, dir: Direction) -> char {
-    match (pos, dir) {
-        ('1', Direction::Down) => '3',
-        ('2', Direction::Down) => '6',
-        ('2', Direction::Right) => '3',
-        ('3', Direction::Up) => '1',
-        ('3', Direction::Down) => '7',
-        ('3', Direction::Left) => '2',
-        ('3', Direction::Right) => '4',
-        ('4', Direction::Down) => '8',
-        ('4', Direction::Left) => '3',
-        ('5', Direction::Right) => '6',
-        ('6', Direction::Up) => '2',
-        ('6', Direction::Down) => 'A',
-        ('6', Direction::Left) => '5',
-        ('6', Direction::Right) => '7',
-        ('7', Direction::Up) => '3',
-        ('7', Direction::Down) => 'B',
-        ('7', Direction::Left) => '6',
-        ('7', Direction::Right) => '8',
-        ('8', Direction::Up) => '4',
-        ('8', Direction::Down) => 'C',
-        ('8', Direction::Left) => '7',
-        ('8', Direction::Right) => '9',
-        ('9', Direction::Left) => '8',
-        ('A', Direction::Up) => '6',
-        ('A', Direction::Right) => 'B',
-        ('B', Direction::Up) => '7',
-        ('B', Direction::Down) => 'D',
-        ('B', Direction::Left) => 'A',
-        ('B', Direction::Right) => 'C',
-        ('C', Direction::Up) => '8',
-        ('C', Direction::Left) => 'B',
-        ('D', Direction::Up) => 'B',
-        _ => pos,
+impl Keypad {
+    pub fn new(layout: &str, start: char) -> Self {
+        let keys: Vec<Vec<Option<char>>> = input::lines(layout)
+            .map(|line| line.chars().map(|c| (c != ' ').then_some(c)).collect())
+            .collect();
+        let mut seen = vec![];
+        let mut start_position = None;
+        for (row, keys) in keys.iter().enumerate() {
+            for (col, &key) in keys.iter().enumerate() {
+                let Some(key) = key else { continue };
+                if seen.contains(&key) {
+                    input::invalid(format!("key {} appears more than once", key));
+                }
+                seen.push(key);
+                if key == start {
+                    start_position = Some((row, col));
+                }
+            }
+        }
+        Self {
+            start: start_position
+                .unwrap_or_else(|| input::invalid(format!("no key {} to start on", start))),
+            keys,
+        }
+    }
+
+    fn key(&self, (row, col): (usize, usize)) -> Option<char> {
+        *self.keys.get(row)?.get(col)?
+    }
+
+    // the moves from a position to a key, and where that key is
+    fn shortest_path(
+        &self,
+        from: (usize, usize),
+        to: char,
+    ) -> Option<(Vec<Direction>, (usize, usize))> {
+        let mut queue = VecDeque::from([(from, vec![])]);
+        let mut seen = vec![from];
+        while let Some((pos, path)) = queue.pop_front() {
+            if self.key(pos) == Some(to) {
+                return Some((path, pos));
+            }
+            for dir in [
+                Direction::Up,
+                Direction::Down,
+                Direction::Left,
+                Direction::Right,
+            ] {
+                let next = self.press(pos, dir);
+                if !seen.contains(&next) {
+                    seen.push(next);
+                    let mut path = path.clone();
+                    path.push(dir);
+                    queue.push_back((next, path));
+                }
+            }
+        }
+        None
+    }
+
+    // The fewest moves, at least one, which leave the finger where it is: one towards the edge or a
+    // gap if there's one next to it, otherwise there and back.
+    fn stay(&self, pos: (usize, usize)) -> Vec<Direction> {
+        [
+            Direction::Up,
+            Direction::Down,
+            Direction::Left,
+            Direction::Right,
+        ]
+        .into_iter()
+        .find(|&dir| self.press(pos, dir) == pos)
+        .map_or_else(|| vec![Direction::Up, Direction::Down], |dir| vec![dir])
     }
+
+    // where the finger ends up after a line of moves, calling visit with each key it moves to
+    fn follow(
+        &self,
+        mut pos: (usize, usize),
+        line: &[Move],
+        mut visit: impl FnMut((usize, usize)),
+    ) -> (usize, usize) {
+        for m in line {
+            for _ in 0..m.count {
+                let next = self.press(pos, m.dir);
+                if next == pos {
+                    // blocked, and will be for the rest of this move
+                    break;
+                }
+                pos = next;
+                visit(pos);
+            }
+        }
+        pos
+    }
+
+    // the keypad with the last position on the path in square brackets and the rest in round ones
+    fn render(&self, path: &[(usize, usize)]) -> String {
+        let current = path[path.len() - 1];
+        self.keys
+            .iter()
+            .enumerate()
+            .map(|(row, keys)| {
+                let line: String = keys
+                    .iter()
+                    .enumerate()
+                    .map(|(col, key)| match key {
+                        None => "   ".to_string(),
+                        Some(key) if (row, col) == current => format!("[{}]", key),
+                        Some(key) if path.contains(&(row, col)) => format!("({})", key),
+                        Some(key) => format!(" {} ", key),
+                    })
+                    .collect();
+                line.trim_end().to_string() + "\n"
+            })
+            .collect()
+    }
+
+    // where the finger ends up after moving one key in the given direction
+    fn press(&self, (row, col): (usize, usize), dir: Direction) -> (usize, usize) {
+        let (down, right) = dir.offset();
+        let next = (
+            row.wrapping_add_signed(down),
+            col.wrapping_add_signed(right),
+        );
+        if self.key(next).is_some() {
+            next
+        } else {
+            (row, col)
+        }
+    }
+}
+
+// The fewest moves which type the code on a keypad, as one line per key.  Each line is a shortest
+// path from the previous key to the next using only up, down, left and right, found by
+// breadth-first search; a repeated key needs an empty line, except at the end where blank lines
+// would be dropped when the instructions are read back, so the finger moves without leaving the key
+// instead.  None if the code has a key which isn't on the keypad.
+pub fn moves(code: &str, keypad: &Keypad) -> Option<Vec<String>> {
+    let mut pos = keypad.start;
+    let keys = code.chars().count();
+    code.chars()
+        .enumerate()
+        .map(|(i, key)| {
+            let (mut path, end) = keypad.shortest_path(pos, key)?;
+            if path.is_empty() && i + 1 == keys {
+                path = keypad.stay(end);
+            }
+            pos = end;
+            Some(path.iter().map(|dir| dir.name()).collect())
+        })
+        .collect()
+}
+
+// The code typed by following the instructions on a keypad.
+pub fn code(input: &str, keypad: &Keypad) -> String {
+    solve(&parse_input(input), keypad)
 }
 
-fn solve(input: &[Vec<Direction>], keypad: fn(char, Direction) -> char) -> String {
-    let mut pos = '5';
+// Pictures of the keypad while following each line of the instructions: one before the first move
+// and one after each key the finger moves to, showing the current key in square brackets and the
+// others passed over on that line in round ones.
+pub fn trace(input: &str, keypad: &Keypad) -> Vec<Vec<String>> {
+    let mut pos = keypad.start;
+    parse_input(input)
+        .iter()
+        .map(|line| {
+            let mut path = vec![pos];
+            let mut frames = vec![keypad.render(&path)];
+            pos = keypad.follow(pos, line, |next| {
+                path.push(next);
+                frames.push(keypad.render(&path));
+            });
+            frames
+        })
+        .collect()
+}
+
+fn solve(input: &[Vec<Move>], keypad: &Keypad) -> String {
+    let mut pos = keypad.start;
     let mut output = "".to_string();
     for line in input {
-        for dir in line {
-            pos = keypad(pos, *dir);
-        }
-        output.push(pos);
+        pos = keypad.follow(pos, line, |_| {});
+        output.push(keypad.key(pos).unwrap());
     }
 
     output
@@ -107,21 +229,105 @@ enum Direction {
     Down,
     Left,
     Right,
+    UpLeft,
+    UpRight,
+    DownLeft,
+    DownRight,
 }
 
-fn parse_input(input: &str) -> Vec<Vec<Direction>> {
-    input
-        .lines()
-        .map(|line| {
-            line.chars()
-                .map(|c| match c {
+impl Direction {
+    // rows down and columns right
+    fn offset(self) -> (isize, isize) {
+        match self {
+            Direction::Up => (-1, 0),
+            Direction::Down => (1, 0),
+            Direction::Left => (0, -1),
+            Direction::Right => (0, 1),
+            Direction::UpLeft => (-1, -1),
+            Direction::UpRight => (-1, 1),
+            Direction::DownLeft => (1, -1),
+            Direction::DownRight => (1, 1),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Direction::Up => "U",
+            Direction::Down => "D",
+            Direction::Left => "L",
+            Direction::Right => "R",
+            Direction::UpLeft => "[UL]",
+            Direction::UpRight => "[UR]",
+            Direction::DownLeft => "[DL]",
+            Direction::DownRight => "[DR]",
+        }
+    }
+}
+
+// A move in one direction, repeated count times.
+#[derive(Debug)]
+struct Move {
+    dir: Direction,
+    count: usize,
+}
+
+// Each line is a list of moves: U, D, L or R, or a diagonal written as [UL], [UR], [DL] or [DR],
+// optionally followed by a number of times to make the move, like U3 or [DR]2.
+fn parse_input(input: &str) -> Vec<Vec<Move>> {
+    input::lines(input)
+        .enumerate()
+        .map(|(row, line)| {
+            let chars: Vec<char> = line.chars().collect();
+            let error = |col: usize, message: String| -> ! {
+                input::invalid(format!("line {}, column {}: {}", row + 1, col + 1, message))
+            };
+            let mut moves = vec![];
+            let mut i = 0;
+            while i < chars.len() {
+                let start = i;
+                let dir = match chars[i] {
                     'U' => Direction::Up,
                     'D' => Direction::Down,
                     'L' => Direction::Left,
                     'R' => Direction::Right,
-                    _ => panic!("Unexpected character: {}", c),
-                })
-                .collect()
+                    '[' => {
+                        i += 3;
+                        match chars.get(start + 1..start + 4) {
+                            Some(['U', 'L', ']']) => Direction::UpLeft,
+                            Some(['U', 'R', ']']) => Direction::UpRight,
+                            Some(['D', 'L', ']']) => Direction::DownLeft,
+                            Some(['D', 'R', ']']) => Direction::DownRight,
+                            _ => error(start, "expected [UL], [UR], [DL] or [DR]".to_string()),
+                        }
+                    }
+                    c => error(i, format!("unexpected character {:?}", c)),
+                };
+                i += 1;
+                let digits = chars[i..].iter().take_while(|c| c.is_ascii_digit()).count();
+                let count = match digits {
+                    0 => 1,
+                    _ => chars[i..i + digits]
+                        .iter()
+                        .collect::<String>()
+                        .parse()
+                        .unwrap_or_else(|_| error(i, "count is too large".to_string())),
+                };
+                i += digits;
+                moves.push(Move { dir, count });
+            }
+            moves
+        })
+        .collect()
+}
+
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    (0..size)
+        .map(|_| {
+            let len = rng.range(1, 500);
+            (0..len)
+                .map(|_| rng.choose(&['U', 'D', 'L', 'R']))
+                .collect::<String>()
+                + "\n"
         })
         .collect()
 }
@@ -131,4 +337,85 @@ fn test() {
     let test_input = "ULL\nRRDDD\nLURDL\nUUUUD\n";
     assert_eq!("1985", Solver::new(test_input).part1());
     assert_eq!("5DB3", Solver::new(test_input).part2());
+
+    let crlf_input = "\u{feff}ULL\r\nRRDDD\r\nLURDL\r\nUUUUD\r\n\r\n";
+    assert_eq!("1985", Solver::new(crlf_input).part1());
+
+    // diagonal and repeated moves
+    assert_eq!(
+        "9144",
+        code(
+            "[DR]\n[UL]9\nD2[UR]L\nR0\n",
+            &Keypad::new(NORMAL_KEYPAD, '5')
+        )
+    );
+    assert_eq!(
+        "9",
+        code("R1000000000000\n", &Keypad::new(DIAMOND_KEYPAD, '5'))
+    );
+    let error = |input: &'static str| {
+        let payload = std::panic::catch_unwind(|| parse_input(input)).unwrap_err();
+        payload.downcast_ref::<String>().unwrap().clone()
+    };
+    assert_eq!(
+        "invalid input: line 2, column 3: unexpected character 'X'",
+        error("UD\nLRX\n")
+    );
+    assert_eq!(
+        "invalid input: line 1, column 2: expected [UL], [UR], [DL] or [DR]",
+        error("U[UU]")
+    );
+    assert_eq!(
+        "invalid input: line 1, column 2: count is too large",
+        error("U99999999999999999999999")
+    );
+
+    let frames = trace("RR\nD\n", &Keypad::new(NORMAL_KEYPAD, '5'));
+    assert_eq!(vec![2, 2], frames.iter().map(Vec::len).collect::<Vec<_>>());
+    assert_eq!(" 1  2  3\n 4 [5] 6\n 7  8  9\n", frames[0][0]);
+    assert_eq!(" 1  2  3\n 4 (5)[6]\n 7  8  9\n", frames[0][1]);
+    assert_eq!(" 1  2  3\n 4  5 (6)\n 7  8 [9]\n", frames[1][1]);
+    let frames = trace("UL\n", &Keypad::new(DIAMOND_KEYPAD, '5'));
+    assert_eq!(
+        "       1\n    2  3  4\n[5] 6  7  8  9\n    A  B  C\n       D\n",
+        frames[0][0]
+    );
+
+    // a phone keypad, starting on 0
+    let phone = Keypad::new("123\n456\n789\n 0\n", '0');
+    assert_eq!("0453", code("L\nULU\nLR\nRUU\n", &phone));
+
+    // the shortest moves type the code they're for
+    let diamond = Keypad::new(DIAMOND_KEYPAD, '5');
+    let lines = moves("5DB3", &diamond).unwrap();
+    assert_eq!(
+        vec![0, 4, 1, 2],
+        lines.iter().map(String::len).collect::<Vec<_>>()
+    );
+    assert_eq!(None, moves("5E", &diamond));
+    // ending on a repeated key stays on it
+    assert_eq!(Some(vec!["U".to_string()]), moves("5", &diamond));
+    assert_eq!(
+        Some(vec!["UD".to_string()]),
+        moves("5", &Keypad::new(NORMAL_KEYPAD, '5'))
+    );
+    assert_eq!(
+        Some(vec![
+            "RDRD".to_string(),
+            "UULL".to_string(),
+            "U".to_string()
+        ]),
+        moves("D55", &diamond)
+    );
+    crate::generate::check(100, |rng| {
+        let (layout, keys) = rng.choose(&[
+            (NORMAL_KEYPAD, "123456789"),
+            (DIAMOND_KEYPAD, "123456789ABCD"),
+        ]);
+        let keypad = Keypad::new(layout, '5');
+        let keys: Vec<char> = keys.chars().collect();
+        let code: String = (0..rng.range(1, 8)).map(|_| rng.choose(&keys)).collect();
+        let lines = moves(&code, &keypad).unwrap();
+        assert_eq!(code, self::code(&lines.join("\n"), &keypad));
+    });
 }
@@ -1,10 +1,12 @@
+use crate::generate::Rng;
+use crate::input;
 use crate::Puzzle;
 
 pub struct Solver {
     input: Vec<Vec<u32>>,
 }
 
-impl Puzzle for Solver {
+impl Puzzle<'_> for Solver {
     fn new(input: &str) -> Self {
         Self {
             input: parse_input(input),
@@ -25,11 +27,12 @@ impl Puzzle for Solver {
 }
 
 fn possible(triangle: &[u32]) -> bool {
-    triangle[0] + triangle[1] > triangle[2]
-        && triangle[0] + triangle[2] > triangle[1]
-        && triangle[1] + triangle[2] > triangle[0]
+    // widened so that the sums can't overflow
+    let [a, b, c] = [triangle[0], triangle[1], triangle[2]].map(u64::from);
+    a + b > c && a + c > b && b + c > a
 }
 
+#[allow(clippy::needless_range_loop)]
 fn count_verticle(input: &[Vec<u32>]) -> usize {
     let mut count = 0;
     for i in 0..input.len() / 3 {
@@ -44,12 +47,23 @@ fn count_verticle(input: &[Vec<u32>]) -> usize {
 }
 
 fn parse_input(input: &str) -> Vec<Vec<u32>> {
-    input
-        .lines()
-        .map(|line| {
-            line.split_whitespace()
-                .map(|s| s.parse().unwrap())
-                .collect()
+    let triangles: Vec<Vec<u32>> = input::integer_grid(input);
+    if triangles.first().is_some_and(|sides| sides.len() != 3) {
+        input::invalid("expected three sides per row");
+    }
+    triangles
+}
+
+// rows of three side lengths, rounded up to a multiple of three rows for part 2
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    (0..size.div_ceil(3) * 3)
+        .map(|_| {
+            format!(
+                "  {:3}  {:3}  {:3}\n",
+                rng.range(1, 999),
+                rng.range(1, 999),
+                rng.range(1, 999)
+            )
         })
         .collect()
 }
@@ -57,4 +71,17 @@ fn parse_input(input: &str) -> Vec<Vec<u32>> {
 #[test]
 fn test() {
     assert!(!possible(&[5, 10, 25]));
+
+    // the order of the sides doesn't matter
+    crate::generate::check(1000, |rng| {
+        let [a, b, c] = [(); 3].map(|_| match rng.range(0, 2) {
+            0 => rng.range(0, 20) as u32,
+            1 => rng.range(0, 1000) as u32,
+            _ => rng.next_u64() as u32,
+        });
+        let expected = possible(&[a, b, c]);
+        for sides in [[a, c, b], [b, a, c], [b, c, a], [c, a, b], [c, b, a]] {
+            assert_eq!(expected, possible(&sides), "{:?}", sides);
+        }
+    });
 }
@@ -1,11 +1,13 @@
+use crate::generate::Rng;
+use crate::input;
 use crate::Puzzle;
 
-pub struct Solver {
-    input: Vec<Room>,
+pub struct Solver<'a> {
+    input: Vec<Room<'a>>,
 }
 
-impl Puzzle for Solver {
-    fn new(input: &str) -> Self {
+impl<'a> Puzzle<'a> for Solver<'a> {
+    fn new(input: &'a str) -> Self {
         Self {
             input: parse_input(input),
         }
@@ -16,8 +18,8 @@ impl Puzzle for Solver {
         self.input
             .iter()
             .filter(|room| room.is_real())
-            .map(|room| room.sector_id)
-            .sum::<u32>()
+            .map(|room| room.sector_id as u64)
+            .sum::<u64>()
             .to_string()
     }
 
@@ -27,28 +29,30 @@ impl Puzzle for Solver {
             .iter()
             .filter(|room| room.is_real())
             .find(|room| room.name() == "northpole object storage")
-            .unwrap()
+            .unwrap_or_else(|| input::invalid("no room named northpole object storage"))
             .sector_id
             .to_string()
     }
 }
 
-fn parse_input(input: &str) -> Vec<Room> {
-    input.lines().map(|line| line.into()).collect()
+fn parse_input(input: &str) -> Vec<Room<'_>> {
+    input::non_empty_lines(input)
+        .map(|line| line.into())
+        .collect()
 }
 
-struct Room {
-    encrypted_name: String,
+struct Room<'a> {
+    encrypted_name: &'a str,
     sector_id: u32,
-    checksum: String,
+    checksum: &'a str,
 }
 
-impl Room {
+impl Room<'_> {
     fn is_real(&self) -> bool {
-        self.checksum == self.calc_checksum()
+        self.checksum.as_bytes() == self.calc_checksum()
     }
 
-    fn calc_checksum(&self) -> String {
+    fn calc_checksum(&self) -> [u8; 5] {
         let mut counts = self
             .encrypted_name
             .chars()
@@ -57,8 +61,8 @@ impl Room {
                 counts[c as usize - 'a' as usize] += 1;
                 counts
             });
-        let mut cs = String::with_capacity(5);
-        for _ in 0..5 {
+        let mut cs = [0; 5];
+        for c in cs.iter_mut() {
             let mut max = 0;
             let mut letter = 0;
             for (i, count) in counts.iter().enumerate() {
@@ -67,7 +71,7 @@ impl Room {
                     letter = i;
                 }
             }
-            cs.push((letter as u8 + b'a') as char);
+            *c = letter as u8 + b'a';
             counts[letter] = 0;
         }
         cs
@@ -79,29 +83,86 @@ impl Room {
             .map(|c| match c {
                 '-' => ' ',
                 'a'..='z' => {
-                    char::from_u32((((c as u32) - 97 + self.sector_id) % 26) + 97).unwrap()
+                    char::from_u32((((c as u32) - 97 + self.sector_id % 26) % 26) + 97).unwrap()
                 }
-                _ => panic!("invalid character in room name: {}", c),
+                _ => input::invalid(format!("invalid character in room name: {}", c)),
             })
             .collect()
     }
 }
 
-impl From<&str> for Room {
-    fn from(s: &str) -> Self {
-        let dash = s.rfind('-').unwrap();
-        let bracket1 = s.rfind('[').unwrap();
-        let bracket2 = s.rfind(']').unwrap();
+// inverse of Room::name
+fn encrypt(name: &str, sector_id: u32) -> String {
+    name.chars()
+        .map(|c| match c {
+            ' ' => '-',
+            'a'..='z' => {
+                char::from_u32((((c as u32) - 97 + 26 - sector_id % 26) % 26) + 97).unwrap()
+            }
+            _ => panic!("invalid character in room name: {}", c),
+        })
+        .collect()
+}
+
+impl<'a> From<&'a str> for Room<'a> {
+    fn from(s: &'a str) -> Self {
+        let invalid = || -> ! { input::invalid(format!("invalid room: {:?}", s)) };
+        let (rest, checksum) = s
+            .strip_suffix(']')
+            .and_then(|rest| rest.rsplit_once('['))
+            .unwrap_or_else(|| invalid());
+        let (encrypted_name, sector_id) = rest.rsplit_once('-').unwrap_or_else(|| invalid());
+        if !encrypted_name
+            .bytes()
+            .all(|b| b == b'-' || b.is_ascii_lowercase())
+        {
+            invalid();
+        }
         Self {
-            encrypted_name: s[..dash].to_string(),
-            sector_id: s[dash + 1..bracket1].parse().unwrap(),
-            checksum: s[bracket1 + 1..bracket2].to_string(),
+            encrypted_name,
+            sector_id: sector_id.parse().unwrap_or_else(|_| invalid()),
+            checksum,
         }
     }
 }
 
+// A mix of real rooms and decoys, one of which is where the north pole objects are stored.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let storage = rng.range(0, size.max(1) - 1);
+    (0..size.max(1))
+        .map(|i| {
+            let sector_id = rng.range(100, 999) as u32;
+            let encrypted_name = if i == storage {
+                encrypt("northpole object storage", sector_id)
+            } else {
+                let words: Vec<String> = (0..rng.range(1, 5))
+                    .map(|_| {
+                        let len = rng.range(1, 10);
+                        rng.letters(len)
+                    })
+                    .collect();
+                words.join("-")
+            };
+            let room = Room {
+                encrypted_name: &encrypted_name,
+                sector_id,
+                checksum: "",
+            };
+            let real = room.calc_checksum();
+            let mut checksum = String::from_utf8_lossy(&real).to_string();
+            if i != storage && rng.one_in(3) {
+                while checksum.as_bytes() == real {
+                    checksum = rng.letters(5);
+                }
+            }
+            format!("{}-{}[{}]\n", encrypted_name, sector_id, checksum)
+        })
+        .collect()
+}
+
 #[test]
 fn test() {
+    #[allow(clippy::redundant_closure)]
     let rooms = [
         "aaaaa-bbb-z-y-x-123[abxyz]",
         "a-b-c-d-e-f-g-h-987[abcde]",
@@ -134,4 +195,23 @@ fn test() {
         "very encrypted name",
         Room::from("qzmt-zixmtkozy-ivhz-343[xxxxx]").name()
     );
+    assert_eq!("qzmt-zixmtkozy-ivhz", encrypt("very encrypted name", 343));
+
+    // decrypting a name undoes encrypting it, for any sector ID
+    crate::generate::check(1000, |rng| {
+        let name = (0..rng.range(1, 4))
+            .map(|_| {
+                let len = rng.range(1, 10);
+                rng.letters(len)
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+        let sector_id = rng.next_u64() as u32;
+        let room = Room {
+            encrypted_name: &encrypt(&name, sector_id),
+            sector_id,
+            checksum: "",
+        };
+        assert_eq!(name, room.name(), "sector ID {}", sector_id);
+    });
 }
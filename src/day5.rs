@@ -1,3 +1,5 @@
+use crate::generate::Rng;
+use crate::input;
 use crate::Puzzle;
 
 use md5::{Digest, Md5};
@@ -6,10 +8,10 @@ pub struct Solver {
     door_id: String,
 }
 
-impl Puzzle for Solver {
+impl Puzzle<'_> for Solver {
     fn new(input: &str) -> Self {
         Self {
-            door_id: input.trim().to_string(),
+            door_id: input::trimmed(input).to_string(),
         }
     }
 
@@ -22,6 +24,39 @@ impl Puzzle for Solver {
     }
 }
 
+// Alternative solver which spreads the hashing over all available cores.
+pub struct ParallelSolver {
+    door_id: String,
+}
+
+impl Puzzle<'_> for ParallelSolver {
+    fn new(input: &str) -> Self {
+        Self {
+            door_id: input::trimmed(input).to_string(),
+        }
+    }
+
+    fn part1(&self) -> String {
+        let mut password = String::with_capacity(8);
+        par_search(&self.door_id, |sixth, _| {
+            password.push(digit_to_ascii(sixth) as char);
+            password.len() == 8
+        });
+        password
+    }
+
+    fn part2(&self) -> String {
+        let mut password = [0u8; 8];
+        par_search(&self.door_id, |sixth, seventh| {
+            if sixth < 8 && password[sixth as usize] == 0 {
+                password[sixth as usize] = digit_to_ascii(seventh);
+            }
+            password.iter().all(|&c| c != 0)
+        });
+        String::from_utf8_lossy(&password).to_string()
+    }
+}
+
 fn bf_password1(door_id: &str) -> String {
     let mut password = String::with_capacity(8);
     let mut hasher = Md5::new();
@@ -71,6 +106,56 @@ fn bf_password2(door_id: &str) -> String {
     String::from_utf8_lossy(&password).to_string()
 }
 
+// number of indexes each thread hashes before the results are collected
+const CHUNK_SIZE: u64 = 50_000;
+
+// Search for hashes starting with five zeroes, handing the sixth and seventh hex digits of each to
+// `found` in index order until it returns true.  Each round, every thread searches its own chunk of
+// the following indexes.
+fn par_search<F>(door_id: &str, mut found: F)
+where
+    F: FnMut(u8, u8) -> bool,
+{
+    let threads = std::thread::available_parallelism().map_or(1, |n| n.get()) as u64;
+    let mut start = 0;
+    loop {
+        let hits: Vec<Vec<(u8, u8)>> = std::thread::scope(|scope| {
+            (0..threads)
+                .map(|t| {
+                    let from = start + t * CHUNK_SIZE;
+                    scope.spawn(move || search_chunk(door_id, from, from + CHUNK_SIZE))
+                })
+                .collect::<Vec<_>>()
+                .into_iter()
+                .map(|handle| handle.join().unwrap())
+                .collect()
+        });
+        for (sixth, seventh) in hits.into_iter().flatten() {
+            if found(sixth, seventh) {
+                return;
+            }
+        }
+        start += threads * CHUNK_SIZE;
+    }
+}
+
+fn search_chunk(door_id: &str, from: u64, to: u64) -> Vec<(u8, u8)> {
+    let mut hasher = Md5::new();
+    let mut i = from.to_string().into_bytes();
+    let mut hits = vec![];
+    for _ in from..to {
+        hasher.update(door_id);
+        hasher.update(&i);
+        let hash = hasher.finalize_reset();
+        increment(&mut i);
+
+        if hash[0] == 0 && hash[1] == 0 && hash[2] < 16 {
+            hits.push((hash[2], hash[3] >> 4));
+        }
+    }
+    hits
+}
+
 // increment a number stored as a Vec of ascii digits
 fn increment(i: &mut Vec<u8>) {
     for digit in i.iter_mut().rev() {
@@ -88,9 +173,18 @@ fn digit_to_ascii(digit: u8) -> u8 {
     digit + if digit < 10 { b'0' } else { b'a' - 10 }
 }
 
+// a door ID of `size` letters
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    rng.letters(size.max(1)) + "\n"
+}
+
 #[test]
 #[ignore] // slow.  run with: cargo test --release -- --ignored
 fn test() {
     assert_eq!("18f47a30", bf_password1("abc"));
     assert_eq!("05ace8e3", bf_password2("abc"));
+
+    let solver = ParallelSolver::new("abc");
+    assert_eq!("18f47a30", solver.part1());
+    assert_eq!("05ace8e3", solver.part2());
 }
@@ -1,11 +1,13 @@
+use crate::generate::Rng;
+use crate::input;
 use crate::Puzzle;
 
-pub struct Solver {
-    codes: Vec<String>,
+pub struct Solver<'a> {
+    codes: Vec<&'a str>,
 }
 
-impl Puzzle for Solver {
-    fn new(input: &str) -> Self {
+impl<'a> Puzzle<'a> for Solver<'a> {
+    fn new(input: &'a str) -> Self {
         Self {
             codes: parse_input(input),
         }
@@ -20,7 +22,7 @@ impl Puzzle for Solver {
     }
 }
 
-fn most_common_letters(codes: &[String]) -> String {
+fn most_common_letters(codes: &[&str]) -> String {
     count_letters(codes)
         .iter()
         .map(|count| {
@@ -38,7 +40,7 @@ fn most_common_letters(codes: &[String]) -> String {
         .collect()
 }
 
-fn least_common_letters(codes: &[String]) -> String {
+fn least_common_letters(codes: &[&str]) -> String {
     count_letters(codes)
         .iter()
         .map(|count| {
@@ -56,7 +58,7 @@ fn least_common_letters(codes: &[String]) -> String {
         .collect()
 }
 
-fn count_letters(codes: &[String]) -> Vec<Vec<usize>> {
+fn count_letters(codes: &[&str]) -> Vec<Vec<usize>> {
     codes
         .iter()
         .fold(vec![vec![0; 26]; codes[0].len()], |mut counts, code| {
@@ -67,8 +69,24 @@ fn count_letters(codes: &[String]) -> Vec<Vec<usize>> {
         })
 }
 
-fn parse_input(input: &str) -> Vec<String> {
-    input.lines().map(|line| line.to_string()).collect()
+fn parse_input(input: &str) -> Vec<&str> {
+    let codes: Vec<&str> = input::non_empty_lines(input).collect();
+    if codes.is_empty() {
+        input::invalid("no messages");
+    }
+    for code in codes.iter() {
+        if code.len() != codes[0].len() {
+            input::invalid(format!("messages differ in length: {}", code));
+        }
+        if !code.bytes().all(|b| b.is_ascii_lowercase()) {
+            input::invalid(format!("unexpected character in message: {}", code));
+        }
+    }
+    codes
+}
+
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    (0..size.max(1)).map(|_| rng.letters(8) + "\n").collect()
 }
 
 #[test]
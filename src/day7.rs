@@ -1,19 +1,21 @@
+use crate::generate::Rng;
+use crate::input;
 use crate::Puzzle;
 
-pub struct Solver {
-    addresses: Vec<IPv7>,
+pub struct Solver<'a> {
+    addresses: Vec<IPv7<'a>>,
 }
 
-struct IPv7 {
-    address: Vec<u8>,
+struct IPv7<'a> {
+    address: &'a [u8],
     // boundary markers (start, end + 1) pairs of indexes into address
     supernets: Vec<(usize, usize)>,
     hypernets: Vec<(usize, usize)>,
 }
 
-impl From<&str> for IPv7 {
-    fn from(s: &str) -> Self {
-        let address = s.as_bytes().to_vec();
+impl<'a> From<&'a str> for IPv7<'a> {
+    fn from(s: &'a str) -> Self {
+        let address = s.as_bytes();
 
         let mut in_hypernet = false;
         let mut pos = 0;
@@ -23,7 +25,10 @@ impl From<&str> for IPv7 {
         // detect boundaries of address parts
         loop {
             if in_hypernet {
-                let end_hypernet = address[pos..].iter().position(|&b| b == b']').unwrap();
+                let end_hypernet = address[pos..]
+                    .iter()
+                    .position(|&b| b == b']')
+                    .unwrap_or_else(|| input::invalid(format!("unclosed hypernet: {}", s)));
                 hypernets.push((pos, pos + end_hypernet));
                 pos += end_hypernet + 1;
             } else if let Some(start_hypernet) = address[pos..].iter().position(|&b| b == b'[') {
@@ -44,7 +49,7 @@ impl From<&str> for IPv7 {
     }
 }
 
-impl IPv7 {
+impl IPv7<'_> {
     // Transport-layer snooping
     // Any ABBA exists in supernet but none exist in hypernet
     fn supports_tls(&self) -> bool {
@@ -77,27 +82,25 @@ impl IPv7 {
 }
 
 fn has_abba(part: &[u8]) -> bool {
-    (0..part.len() - 3)
-        .map(|i| &part[i..i + 4])
+    part.windows(4)
         .any(|s| s[0] == s[3] && s[1] == s[2] && s[0] != s[1])
 }
 
 fn find_abas(part: &[u8]) -> Vec<(u8, u8)> {
-    (0..part.len() - 2)
-        .map(|i| &part[i..i + 3])
+    part.windows(3)
         .filter(|s| s[0] == s[2] && s[0] != s[1])
         .map(|s| (s[0], s[1]))
         .collect()
 }
 
 fn has_bab(part: &[u8], a: u8, b: u8) -> bool {
-    (0..part.len() - 2).any(|i| part[i] == b && part[i + 1] == a && part[i + 2] == b)
+    part.windows(3).any(|s| s == [b, a, b])
 }
 
-impl Puzzle for Solver {
-    fn new(input: &str) -> Self {
+impl<'a> Puzzle<'a> for Solver<'a> {
+    fn new(input: &'a str) -> Self {
         Self {
-            addresses: input.lines().map(IPv7::from).collect(),
+            addresses: input::non_empty_lines(input).map(IPv7::from).collect(),
         }
     }
 
@@ -118,6 +121,32 @@ impl Puzzle for Solver {
     }
 }
 
+// Addresses made of alternating supernet and hypernet sequences.  Some sequences have an ABBA or
+// ABA planted in them so that a reasonable number of addresses support TLS or SSL.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let sequence = |rng: &mut Rng| {
+        let len = rng.range(4, 16);
+        let mut s: Vec<char> = rng.letters(len).chars().collect();
+        let (a, b) = (rng.letter(), rng.letter());
+        let i = rng.range(0, s.len() - 4);
+        if a != b && rng.one_in(4) {
+            s[i..i + 4].copy_from_slice(&[a, b, b, a]);
+        } else if a != b && rng.one_in(3) {
+            s[i..i + 3].copy_from_slice(&[a, b, a]);
+        }
+        s.into_iter().collect::<String>()
+    };
+    (0..size)
+        .map(|_| {
+            let mut address = sequence(rng);
+            for _ in 0..rng.range(1, 3) {
+                address += &format!("[{}]{}", sequence(rng), sequence(rng));
+            }
+            address + "\n"
+        })
+        .collect()
+}
+
 #[test]
 fn test() {
     assert!(IPv7::from("abba[mnop]qrst").supports_tls());
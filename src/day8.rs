@@ -1,8 +1,14 @@
 use std::collections::VecDeque;
 use std::fmt;
 
+use crate::generate::Rng;
+use crate::input;
+use crate::trace;
 use crate::Puzzle;
 
+const WIDTH: usize = 50;
+const HEIGHT: usize = 6;
+
 pub struct Solver {
     input: Vec<Instruction>,
 }
@@ -14,7 +20,9 @@ struct Screen {
 impl Screen {
     fn new() -> Self {
         Self {
-            pixels: (0..6).map(|_| VecDeque::from([false; 50])).collect(),
+            pixels: (0..HEIGHT)
+                .map(|_| VecDeque::from([false; WIDTH]))
+                .collect(),
         }
     }
 
@@ -27,13 +35,13 @@ impl Screen {
     }
 
     fn rotate_row(&mut self, row: usize, by: usize) {
-        self.pixels[row].rotate_right(by % 50);
+        self.pixels[row].rotate_right(by % WIDTH);
     }
 
     fn rotate_column(&mut self, col: usize, by: usize) {
         let mut new_column =
             VecDeque::from(self.pixels.iter().map(|row| row[col]).collect::<Vec<_>>());
-        new_column.rotate_right(by % 6);
+        new_column.rotate_right(by % HEIGHT);
         for (row, value) in new_column.iter().enumerate() {
             self.pixels[row][col] = *value;
         }
@@ -63,7 +71,7 @@ enum Instruction {
     RotateColumn(usize, usize),
 }
 
-impl Puzzle for Solver {
+impl Puzzle<'_> for Solver {
     fn new(input: &str) -> Self {
         Self {
             input: parse_input(input),
@@ -87,34 +95,72 @@ fn process(instructions: &[Instruction]) -> Screen {
             Instruction::RotateColumn(col, by) => screen.rotate_column(*col, *by),
             Instruction::RotateRow(row, by) => screen.rotate_row(*row, *by),
         }
+        trace::event!(
+            "day8.instruction",
+            instruction = format!("{:?}", instruction),
+            lit = screen.count_lit(),
+        );
     }
     screen
 }
 
 fn parse_input(input: &str) -> Vec<Instruction> {
-    input
-        .lines()
+    input::non_empty_lines(input)
         .map(|line| {
-            if line.starts_with("rect ") {
-                Instruction::Rect(
-                    line[5..line.find('x').unwrap()].parse().unwrap(),
-                    line[line.find('x').unwrap() + 1..].parse().unwrap(),
-                )
-            } else if line.starts_with("rotate row y=") {
-                let by = line.find(" by ").unwrap();
-                Instruction::RotateRow(
-                    line[13..by].parse().unwrap(),
-                    line[by + 4..].parse().unwrap(),
-                )
-            } else if line.starts_with("rotate column x=") {
-                let by = line.find(" by ").unwrap();
-                Instruction::RotateColumn(
-                    line[16..by].parse().unwrap(),
-                    line[by + 4..].parse().unwrap(),
-                )
+            let invalid = || -> ! { input::invalid(format!("invalid instruction: {}", line)) };
+            // a number no greater than max
+            let number = |s: &str, max: usize| match s.parse() {
+                Ok(n) if n <= max => n,
+                _ => invalid(),
+            };
+            if let Some(size) = line.strip_prefix("rect ") {
+                let (width, height) = size.split_once('x').unwrap_or_else(|| invalid());
+                Instruction::Rect(number(width, WIDTH), number(height, HEIGHT))
+            } else if let Some(rotation) = line.strip_prefix("rotate row y=") {
+                let (row, by) = rotation.split_once(" by ").unwrap_or_else(|| invalid());
+                Instruction::RotateRow(number(row, HEIGHT - 1), number(by, usize::MAX))
+            } else if let Some(rotation) = line.strip_prefix("rotate column x=") {
+                let (col, by) = rotation.split_once(" by ").unwrap_or_else(|| invalid());
+                Instruction::RotateColumn(number(col, WIDTH - 1), number(by, usize::MAX))
             } else {
-                panic!("Invalid instruction: {}", line);
+                invalid()
             }
         })
         .collect()
 }
+
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    (0..size)
+        .map(|_| match rng.range(0, 2) {
+            0 => format!("rect {}x{}\n", rng.range(1, 50), rng.range(1, 6)),
+            1 => format!("rotate row y={} by {}\n", rng.range(0, 5), rng.range(1, 49)),
+            _ => format!(
+                "rotate column x={} by {}\n",
+                rng.range(0, 49),
+                rng.range(1, 5)
+            ),
+        })
+        .collect()
+}
+
+#[test]
+fn test() {
+    let screen = process(&parse_input(
+        "rect 3x2\nrotate column x=1 by 1\nrotate row y=0 by 4\nrotate column x=1 by 1\n",
+    ));
+    assert_eq!(6, screen.count_lit());
+
+    // rotating a row by the width of the screen leaves it unchanged
+    crate::generate::check(100, |rng| {
+        let size = rng.range(1, 50);
+        let mut screen = process(&parse_input(&generate(rng, size)));
+        let before = screen.pixels.clone();
+        let row = rng.range(0, HEIGHT - 1);
+        screen.rotate_row(row, WIDTH);
+        assert_eq!(before, screen.pixels);
+        for _ in 0..WIDTH {
+            screen.rotate_row(row, 1);
+        }
+        assert_eq!(before, screen.pixels);
+    });
+}
@@ -0,0 +1,48 @@
+// Run each implementation of a day several times and report any part whose answer changes between
+// runs.  Every run happens on a fresh thread; std's `RandomState` picks new random keys per thread,
+// so hash sets and maps iterate in a different order each time, which shakes out answers that
+// accidentally depend on that order.
+
+use std::collections::HashSet;
+use std::thread;
+
+use adventofcode_2016::implementations;
+
+pub const DEFAULT_RUNS: usize = 5;
+
+pub fn check(day: usize, input: &str, runs: usize) {
+    for (name, new) in implementations(day) {
+        let answers: Vec<[String; 2]> = (0..runs)
+            .map(|_| {
+                thread::scope(|scope| {
+                    scope
+                        .spawn(|| {
+                            let solver = new(input);
+                            [solver.part1(), solver.part2()]
+                        })
+                        .join()
+                        .unwrap()
+                })
+            })
+            .collect();
+
+        for part in 0..2 {
+            let distinct: HashSet<&String> = answers.iter().map(|answer| &answer[part]).collect();
+            println!(
+                "Day {:02}, part {}: {:12} {}",
+                day,
+                part + 1,
+                name,
+                if distinct.len() == 1 {
+                    format!("same answer in {} runs", runs)
+                } else {
+                    format!(
+                        "NONDETERMINISTIC: {} different answers in {} runs",
+                        distinct.len(),
+                        runs
+                    )
+                }
+            );
+        }
+    }
+}
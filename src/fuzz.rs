@@ -0,0 +1,127 @@
+// A simple built-in fuzzer.  Generated inputs are randomly mutated and fed to every implementation
+// of a day; any panic other than a solver rejecting the input with `input::invalid` is a crash.
+// Crashing inputs are minimised and saved in fuzz/regressions/, and the test below replays them.
+
+use std::fs;
+use std::panic;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use adventofcode_2016::generate::{self, Rng};
+use adventofcode_2016::{implementations, input};
+
+use crate::minimise::{self, Predicate};
+
+pub const REGRESSIONS: &str = "fuzz/regressions";
+
+// characters likely to upset a parser
+const INTERESTING: &[char] = &[
+    'a', 'z', 'A', 'Z', '0', '9', '-', '[', ']', ',', ' ', '\n', 'x', '=', 'é', 'L', 'R', 'U',
+];
+
+// where the most recent panic happened, recorded by the panic hook while fuzzing
+static LOCATION: Mutex<Option<String>> = Mutex::new(None);
+
+pub fn fuzz(day: usize, iterations: usize, seed: u64) {
+    let generate = match generate::generator(day) {
+        Some(generate) => generate,
+        None => {
+            eprintln!("No generator for day {}", day);
+            return;
+        }
+    };
+
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|info| {
+        *LOCATION.lock().unwrap() = info.location().map(|location| location.to_string());
+    }));
+
+    let mut rng = Rng::new(seed);
+    let mut seen = vec![];
+    for _ in 0..iterations {
+        let size = rng.range(1, 20);
+        let generated = generate(&mut rng, size);
+        let input = mutate(&mut rng, &generated);
+        if let Some(message) = crash(day, &input) {
+            // only report each crash site once
+            let location = LOCATION.lock().unwrap().take().unwrap_or_default();
+            if seen.contains(&location) {
+                continue;
+            }
+            seen.push(location.clone());
+            let minimal = minimise::minimise(day, &input, Predicate::Crash).unwrap_or(input);
+            let path = save(day, &minimal);
+            println!(
+                "Day {:02}: {} at {} ({})",
+                day,
+                message,
+                location,
+                path.display()
+            );
+        }
+    }
+
+    panic::set_hook(hook);
+}
+
+// The message of the panic if any implementation of the day crashes on this input.  Day 5 only
+// has its parser exercised, since solving it takes millions of hashes.
+pub fn crash(day: usize, input: &str) -> Option<String> {
+    implementations(day).into_iter().find_map(|(_, new)| {
+        let result = panic::catch_unwind(|| {
+            let solver = new(input);
+            if day != 5 {
+                solver.part1();
+                solver.part2();
+            }
+        });
+        match result {
+            Err(payload) if !input::is_invalid(payload.as_ref()) => {
+                Some(input::panic_message(payload.as_ref()))
+            }
+            _ => None,
+        }
+    })
+}
+
+// a few random single character insertions, deletions, replacements, or a truncation
+fn mutate(rng: &mut Rng, input: &str) -> String {
+    let mut chars: Vec<char> = input.chars().collect();
+    for _ in 0..rng.range(1, 4) {
+        let i = rng.range(0, chars.len());
+        match rng.range(0, 9) {
+            0 => chars.truncate(i),
+            1..=3 if i < chars.len() => {
+                chars.remove(i);
+            }
+            4..=6 if i < chars.len() => chars[i] = rng.choose(INTERESTING),
+            _ => chars.insert(i, rng.choose(INTERESTING)),
+        }
+    }
+    chars.into_iter().collect()
+}
+
+// save as fuzz/regressions/dayN-HASH.txt, named by a hash of the input so that rediscovering the
+// same crash doesn't create a new file
+fn save(day: usize, input: &str) -> PathBuf {
+    let hash = input.bytes().fold(0xcbf29ce484222325u64, |hash, b| {
+        (hash ^ b as u64).wrapping_mul(0x100000001b3)
+    });
+    let dir = Path::new(REGRESSIONS);
+    fs::create_dir_all(dir).unwrap();
+    let path = dir.join(format!("day{}-{:016x}.txt", day, hash));
+    fs::write(&path, input).unwrap();
+    path
+}
+
+#[test]
+fn test() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join(REGRESSIONS);
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        let name = path.file_name().unwrap().to_str().unwrap();
+        let day = name[3..name.find('-').unwrap()].parse().unwrap();
+        let input = fs::read_to_string(&path).unwrap();
+        assert_eq!(None, crash(day, &input), "{}", name);
+    }
+}
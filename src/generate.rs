@@ -0,0 +1,117 @@
+// Random but valid puzzle inputs for stress testing the solvers.  Each day module provides a
+// `generate` function taking a seeded random number generator and a size, which is roughly the
+// number of lines (or items) to produce.
+
+// Generates a random input for the given day, if that day has a generator.
+pub type Generator = fn(&mut Rng, usize) -> String;
+
+pub fn generator(day: usize) -> Option<Generator> {
+    match day {
+        #[cfg(feature = "day1")]
+        1 => Some(crate::day1::generate),
+        #[cfg(feature = "day2")]
+        2 => Some(crate::day2::generate),
+        #[cfg(feature = "day3")]
+        3 => Some(crate::day3::generate),
+        #[cfg(feature = "day4")]
+        4 => Some(crate::day4::generate),
+        #[cfg(feature = "day5")]
+        5 => Some(crate::day5::generate),
+        #[cfg(feature = "day6")]
+        6 => Some(crate::day6::generate),
+        #[cfg(feature = "day7")]
+        7 => Some(crate::day7::generate),
+        #[cfg(feature = "day8")]
+        8 => Some(crate::day8::generate),
+        _ => None,
+    }
+}
+
+// SplitMix64; small, fast, and good enough for making up puzzle inputs.  The same seed always
+// produces the same sequence.
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    // a number in the inclusive range min..=max
+    pub fn range(&mut self, min: usize, max: usize) -> usize {
+        min + (self.next_u64() % (max - min + 1) as u64) as usize
+    }
+
+    // true with a probability of 1 in n
+    pub fn one_in(&mut self, n: usize) -> bool {
+        self.range(1, n) == 1
+    }
+
+    pub fn choose<T: Copy>(&mut self, items: &[T]) -> T {
+        items[self.range(0, items.len() - 1)]
+    }
+
+    pub fn letter(&mut self) -> char {
+        (b'a' + self.range(0, 25) as u8) as char
+    }
+
+    pub fn letters(&mut self, len: usize) -> String {
+        (0..len).map(|_| self.letter()).collect()
+    }
+}
+
+// Checks a property against many random cases.  Each case has its own seed, which is reported if
+// the property fails so that the case can be reproduced with `Rng::new(seed)`.
+#[cfg(test)]
+pub fn check(cases: u64, property: impl Fn(&mut Rng)) {
+    use std::panic;
+
+    for seed in 0..cases {
+        let result = panic::catch_unwind(panic::AssertUnwindSafe(|| property(&mut Rng::new(seed))));
+        if let Err(payload) = result {
+            eprintln!("property failed with seed {}", seed);
+            panic::resume_unwind(payload);
+        }
+    }
+}
+
+#[test]
+fn test() {
+    let mut a = Rng::new(42);
+    let mut b = Rng::new(42);
+    assert_eq!(
+        (0..10).map(|_| a.next_u64()).collect::<Vec<_>>(),
+        (0..10).map(|_| b.next_u64()).collect::<Vec<_>>()
+    );
+
+    // every implementation of each day should be able to solve its generated inputs, and agree
+    for day in (1..=25).filter(|&day| day != 5) {
+        if let Some(generate) = generator(day) {
+            for seed in 0..5 {
+                let input = generate(&mut Rng::new(seed), 100);
+                let answers: Vec<_> = crate::implementations(day)
+                    .iter()
+                    .map(|(_, new)| {
+                        let solver = new(&input);
+                        (solver.part1(), solver.part2())
+                    })
+                    .collect();
+                assert!(
+                    answers.iter().all(|answer| *answer == answers[0]),
+                    "day {} seed {}: implementations disagree",
+                    day,
+                    seed
+                );
+            }
+        }
+    }
+}
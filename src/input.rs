@@ -0,0 +1,109 @@
+// Helpers shared by the day parsers so that they all treat input the same way: a UTF-8 byte order
+// mark is ignored, CRLF line endings are accepted and trailing whitespace (including blank lines
+// at the end of the file) is removed.
+
+use std::any::Any;
+use std::fmt::{Debug, Display};
+use std::str::FromStr;
+
+// Solvers reject input they can't handle by panicking with a message starting with this, which
+// distinguishes it from a bug.
+pub const INVALID: &str = "invalid input: ";
+
+pub fn invalid(message: impl Display) -> ! {
+    panic!("{}{}", INVALID, message)
+}
+
+// whether a panic payload came from `invalid`
+pub fn is_invalid(payload: &(dyn Any + Send)) -> bool {
+    payload
+        .downcast_ref::<String>()
+        .is_some_and(|message| message.starts_with(INVALID))
+}
+
+// the message a panic was raised with, if it was given one
+pub fn panic_message(payload: &(dyn Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|message| message.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "solver panicked".to_string())
+}
+
+// the whole input with any BOM and trailing whitespace removed
+pub fn trimmed(input: &str) -> &str {
+    input.strip_prefix('\u{feff}').unwrap_or(input).trim_end()
+}
+
+// each line with trailing whitespace removed
+pub fn lines(input: &str) -> impl Iterator<Item = &str> {
+    trimmed(input).lines().map(str::trim_end)
+}
+
+// as `lines` but skipping any which are blank
+pub fn non_empty_lines(input: &str) -> impl Iterator<Item = &str> {
+    lines(input).filter(|line| !line.is_empty())
+}
+
+// items separated by commas, which may be surrounded by whitespace or split over lines
+pub fn comma_list(input: &str) -> impl Iterator<Item = &str> {
+    trimmed(input).split(',').map(str::trim)
+}
+
+// rows of whitespace separated integers.  every row must have the same number of columns
+pub fn integer_grid<T>(input: &str) -> Vec<Vec<T>>
+where
+    T: FromStr,
+    T::Err: Debug,
+{
+    let rows: Vec<(usize, Vec<T>)> = lines(input)
+        .enumerate()
+        .filter(|(_, line)| !line.is_empty())
+        .map(|(i, line)| {
+            let row = line
+                .split_whitespace()
+                .map(|s| match s.parse() {
+                    Ok(n) => n,
+                    Err(e) => invalid(format!("line {}: invalid integer {:?}: {:?}", i + 1, s, e)),
+                })
+                .collect();
+            (i + 1, row)
+        })
+        .collect();
+    if let Some((line, row)) = rows.iter().find(|(_, row)| row.len() != rows[0].1.len()) {
+        invalid(format!(
+            "line {}: expected {} columns, found {}",
+            line,
+            rows[0].1.len(),
+            row.len()
+        ));
+    }
+    rows.into_iter().map(|(_, row)| row).collect()
+}
+
+#[test]
+fn test() {
+    assert_eq!("abc", trimmed("\u{feff}abc \r\n\n"));
+    assert_eq!(
+        vec!["a", "", "b"],
+        lines("\u{feff}a \r\n\r\nb\t\r\n\r\n").collect::<Vec<_>>()
+    );
+    assert_eq!(
+        vec!["a", "b"],
+        non_empty_lines("a\r\n\r\nb\n").collect::<Vec<_>>()
+    );
+    assert_eq!(
+        vec!["R2", "L3", "R1"],
+        comma_list("R2, L3,\r\nR1\r\n").collect::<Vec<_>>()
+    );
+    assert_eq!(
+        vec![vec![1, 2], vec![3, 4]],
+        integer_grid::<u32>("  1  2\r\n3 4 \r\n\r\n")
+    );
+}
+
+#[test]
+#[should_panic(expected = "invalid input: line 3: expected 2 columns, found 1")]
+fn test_ragged_grid() {
+    integer_grid::<u32>("1 2\n\n3\n");
+}
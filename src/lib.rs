@@ -0,0 +1,93 @@
+// Solutions to Advent of Code 2016.  Each day is behind a cargo feature of the same name (all
+// enabled by default), so only the solvers which are needed have to be compiled.
+
+#[cfg(feature = "day1")]
+pub mod day1;
+#[cfg(feature = "day2")]
+pub mod day2;
+#[cfg(feature = "day3")]
+pub mod day3;
+#[cfg(feature = "day4")]
+pub mod day4;
+#[cfg(feature = "day5")]
+pub mod day5;
+#[cfg(feature = "day6")]
+pub mod day6;
+#[cfg(feature = "day7")]
+pub mod day7;
+#[cfg(feature = "day8")]
+pub mod day8;
+pub mod generate;
+pub mod input;
+pub mod trace;
+
+// Solvers may borrow from the input, which outlives them.
+pub trait Puzzle<'a> {
+    fn new(input: &'a str) -> Self
+    where
+        Self: Sized;
+    fn part1(&self) -> String;
+    fn part2(&self) -> String;
+}
+
+// Constructs a boxed solver for a day's input.
+pub type Constructor = for<'a> fn(&'a str) -> Box<dyn Puzzle<'a> + 'a>;
+
+#[allow(unused_macros)] // when built without any days
+macro_rules! implementation {
+    ($name:expr, $solver:ty) => {
+        (
+            $name,
+            (|input: &str| -> Box<dyn Puzzle<'_> + '_> { Box::new(<$solver>::new(input)) })
+                as Constructor,
+        )
+    };
+}
+
+// All named implementations of each day's puzzle.  The first is the default used by a normal run,
+// the others are only used when cross-checking.
+pub fn implementations(day: usize) -> Vec<(&'static str, Constructor)> {
+    match day {
+        #[cfg(feature = "day1")]
+        1 => vec![
+            implementation!("stepwise", day1::Solver),
+            implementation!("segments", day1::SegmentSolver),
+        ],
+        #[cfg(feature = "day2")]
+        2 => vec![implementation!("default", day2::Solver)],
+        #[cfg(feature = "day3")]
+        3 => vec![implementation!("default", day3::Solver)],
+        #[cfg(feature = "day4")]
+        4 => vec![implementation!("default", day4::Solver)],
+        #[cfg(feature = "day5")]
+        5 => vec![
+            implementation!("sequential", day5::Solver),
+            implementation!("parallel", day5::ParallelSolver),
+        ],
+        #[cfg(feature = "day6")]
+        6 => vec![implementation!("default", day6::Solver)],
+        #[cfg(feature = "day7")]
+        7 => vec![implementation!("default", day7::Solver)],
+        #[cfg(feature = "day8")]
+        8 => vec![implementation!("default", day8::Solver)],
+        _ => vec![],
+    }
+}
+
+// The implementation to run on input from elsewhere, whose time and memory are bounded by the
+// size of the input rather than by what it asks for.  Day 1's default steps one block at a time,
+// so a few huge walks would use gigabytes.
+pub fn untrusted_implementation(day: usize) -> Option<Constructor> {
+    match day {
+        #[cfg(feature = "day1")]
+        1 => Some(implementation!("segments", day1::SegmentSolver).1),
+        _ => implementations(day).first().map(|&(_, new)| new),
+    }
+}
+
+// days which have been compiled in
+pub fn days() -> Vec<usize> {
+    (1..=25)
+        .filter(|&day| !implementations(day).is_empty())
+        .collect()
+}
@@ -1,87 +1,250 @@
 use std::io::Read;
 use std::time::Instant;
 
-mod day1;
-mod day2;
-mod day3;
-mod day4;
-mod day5;
-mod day6;
-mod day7;
-mod day8;
-
-trait Puzzle {
-    fn new(input: &str) -> Self;
-    fn part1(&self) -> String;
-    fn part2(&self) -> String;
-}
+use adventofcode_2016::{days, generate, implementations, trace};
+
+mod determinism;
+mod fuzz;
+mod minimise;
+mod report;
+mod scale;
+#[cfg(feature = "day1")]
+mod step;
+mod visualize;
 
 fn main() {
-    let args: Vec<_> = std::env::args().collect();
-
-    if args.len() == 2 {
-        run(args[1].parse().unwrap());
-    } else {
-        let t0 = Instant::now();
-        for day in 1..=25 {
-            run(day);
+    let mut args: Vec<_> = std::env::args().collect();
+
+    if let Some(i) = args.iter().position(|arg| arg.starts_with("--trace")) {
+        let arg = args.remove(i);
+        let filename = arg.strip_prefix("--trace=").unwrap_or(trace::DEFAULT_FILE);
+        trace::enable(filename).unwrap();
+    }
+    let slow = match args.iter().position(|arg| arg.starts_with("--slow=")) {
+        Some(i) => args.remove(i)["--slow=".len()..].parse().unwrap(),
+        None => report::DEFAULT_SLOW,
+    };
+    let mut report = report::Report::new(slow);
+    let visualize_arg = args
+        .iter()
+        .position(|arg| arg.starts_with("--visualize"))
+        .map(|i| args.remove(i));
+    let animate = match args.iter().position(|arg| arg == "--animate") {
+        Some(i) => {
+            args.remove(i);
+            true
+        }
+        None => false,
+    };
+
+    match args.get(1).map(|arg| arg.as_str()) {
+        Some("crosscheck") => match args.get(2) {
+            Some(day) => crosscheck(day.parse().unwrap()),
+            None => days().into_iter().for_each(crosscheck),
+        },
+        Some("determinism") => {
+            let runs = args
+                .get(3)
+                .map_or(determinism::DEFAULT_RUNS, |runs| runs.parse().unwrap());
+            let days = match args.get(2) {
+                Some(day) => vec![day.parse().unwrap()],
+                None => days(),
+            };
+            for day in days {
+                if let Some(input) = read_input(day) {
+                    determinism::check(day, &input, runs);
+                }
+            }
+        }
+        Some("fuzz") => {
+            let iterations = args.get(3).map_or(10000, |n| n.parse().unwrap());
+            let seed = args.get(4).map_or(1, |seed| seed.parse().unwrap());
+            let days = match args.get(2) {
+                Some(day) => vec![day.parse().unwrap()],
+                None => days(),
+            };
+            for day in days {
+                fuzz::fuzz(day, iterations, seed);
+            }
+        }
+        Some("generate") => print_generated(&args[2..]),
+        #[cfg(feature = "day2")]
+        Some("keypad") => keypad(&args[2..]),
+        Some("minimise") => minimise(&args[2..]),
+        #[cfg(feature = "day1")]
+        Some("step") => {
+            let input = match args.get(2) {
+                Some(filename) => read_file(filename).ok(),
+                None => read_input(1),
+            };
+            let input = input.expect("Usage: step [FILE]");
+            step::step(&input, std::io::stdin().lock(), std::io::stdout());
+        }
+        Some("scale") => {
+            let day = args.get(2).expect("Usage: scale DAY [MAX_SIZE]");
+            let max_size = args.get(3).map_or(1_000_000, |size| size.parse().unwrap());
+            scale::scale(day.parse().unwrap(), max_size);
+        }
+        Some(day) => {
+            let day = day.parse().unwrap();
+            run(&mut report, day);
+            if visualize_arg.is_some() || animate {
+                if let Some(input) = read_input(day) {
+                    let filename = visualize_arg
+                        .as_deref()
+                        .and_then(|arg| arg.strip_prefix("--visualize="));
+                    visualize::visualize(day, &input, filename, animate);
+                }
+            }
+        }
+        None => {
+            let t0 = Instant::now();
+            for day in days() {
+                run(&mut report, day);
+            }
+            report.summary();
+            println!(
+                "{:>80}",
+                format!("TOTAL: {:.3}s", t0.elapsed().as_secs_f64())
+            );
         }
-        println!(
-            "{:>80}",
-            format!("TOTAL: {:.3}s", t0.elapsed().as_secs_f64())
-        );
     }
+
+    trace::flush();
 }
 
-macro_rules! run {
-    ($day:expr, $solver:ty, $input:expr) => {{
-        let t0 = Instant::now();
-        let solver = <$solver>::new($input);
-        measure(&format!("Day {:02}, part 1", $day), t0, || solver.part1());
-        let t0 = Instant::now();
-        measure(&format!("Day {:02}, part 2", $day), t0, || solver.part2());
-    }};
+fn run(report: &mut report::Report, day: usize) {
+    if let Some(input) = read_input(day) {
+        if let Some((_, new)) = implementations(day).first() {
+            // part 1's time includes parsing the input
+            let t0 = Instant::now();
+            let solver = new(&input);
+            let part1 = solver.part1();
+            report.part(day, 1, &part1, t0.elapsed().as_secs_f64());
+            let t0 = Instant::now();
+            let part2 = solver.part2();
+            report.part(day, 2, &part2, t0.elapsed().as_secs_f64());
+        }
+    }
 }
 
-fn run(day: usize) {
-    let filename = format!("input/day{}.txt", day);
-    if let Ok(input) = read_file(&filename) {
-        match day {
-            1 => run!(day, day1::Solver, &input),
-            2 => run!(day, day2::Solver, &input),
-            3 => run!(day, day3::Solver, &input),
-            4 => run!(day, day4::Solver, &input),
-            5 => run!(day, day5::Solver, &input),
-            6 => run!(day, day6::Solver, &input),
-            7 => run!(day, day7::Solver, &input),
-            8 => run!(day, day8::Solver, &input),
-            _ => (),
+// Run every implementation of a day, reporting answers which disagree with the default
+// implementation and how long each took relative to it.
+fn crosscheck(day: usize) {
+    let input = match read_input(day) {
+        Some(input) => input,
+        None => return,
+    };
+
+    let results: Vec<_> = implementations(day)
+        .into_iter()
+        .map(|(name, new)| {
+            let t0 = Instant::now();
+            let solver = new(&input);
+            let part1 = solver.part1();
+            let t1 = t0.elapsed().as_secs_f64();
+            let t0 = Instant::now();
+            let part2 = solver.part2();
+            let t2 = t0.elapsed().as_secs_f64();
+            (name, [(part1, t1), (part2, t2)])
+        })
+        .collect();
+
+    for part in 0..2 {
+        if let Some((_, answers)) = results.first() {
+            let (expected, baseline) = &answers[part];
+            for (name, answers) in results.iter() {
+                let (answer, elapsed) = &answers[part];
+                println!(
+                    "Day {:02}, part {}: {:12} {:32} {:.3}s {:>7.2}x{}",
+                    day,
+                    part + 1,
+                    name,
+                    answer.lines().next().unwrap_or(""),
+                    elapsed,
+                    elapsed / baseline,
+                    if answer == expected { "" } else { "  MISMATCH" }
+                );
+            }
         }
     }
 }
 
-fn measure<F>(label: &str, t0: Instant, f: F)
-where
-    F: FnOnce() -> String,
-{
-    print!("{}: ", label);
-    let result = f();
+// generate DAY [SEED] [SIZE]: print a random input for a day
+fn print_generated(args: &[String]) {
+    let day = args.first().expect("Usage: generate DAY [SEED] [SIZE]");
+    let day = day.parse().unwrap();
+    let seed = args.get(1).map_or(1, |seed| seed.parse().unwrap());
+    let size = args.get(2).map_or(1000, |size| size.parse().unwrap());
+    match generate::generator(day) {
+        Some(generate) => print!("{}", generate(&mut generate::Rng::new(seed), size)),
+        None => eprintln!("No generator for day {}", day),
+    }
+}
+
+// keypad LAYOUT [START] [FILE]: the day 2 code typed on the keypad drawn in LAYOUT, starting on the
+// START key (5 by default), following the instructions in FILE or the day's input
+#[cfg(feature = "day2")]
+fn keypad(args: &[String]) {
+    let usage = "Usage: keypad LAYOUT [START] [FILE]";
+    let layout = read_file(args.first().expect(usage)).unwrap();
+    let start = args
+        .get(1)
+        .map_or('5', |start| start.chars().next().expect(usage));
+    let input = match args.get(2) {
+        Some(filename) => read_file(filename).ok(),
+        None => read_input(2),
+    };
+    let keypad = adventofcode_2016::day2::Keypad::new(&layout, start);
     println!(
-        "{:56} {1:.3}s",
-        if result.contains('\n') {
-            result.lines().next().unwrap()
-        } else {
-            &result
-        },
-        t0.elapsed().as_secs_f64()
+        "{}",
+        adventofcode_2016::day2::code(&input.expect(usage), &keypad)
     );
-    if result.contains('\n') {
-        for line in result.lines().skip(1) {
-            println!("{:16}{}", "", line);
+}
+
+// minimise DAY panic|mismatch|crash FILE [OUTPUT]: shrink an input while the solvers still panic
+// or disagree, writing the result to OUTPUT (by default FILE.min)
+fn minimise(args: &[String]) {
+    let usage = "Usage: minimise DAY panic|mismatch|crash FILE [OUTPUT]";
+    if args.len() < 3 {
+        panic!("{}", usage);
+    }
+    let day = args[0].parse().unwrap();
+    let predicate = minimise::Predicate::parse(&args[1]).expect(usage);
+    let input = read_file(&args[2]).unwrap();
+    let output = args
+        .get(3)
+        .cloned()
+        .unwrap_or_else(|| format!("{}.min", args[2]));
+    match minimise::minimise(day, &input, predicate) {
+        Some(minimal) => {
+            std::fs::write(&output, &minimal).unwrap();
+            println!(
+                "Reduced {} bytes to {}, written to {}",
+                input.len(),
+                minimal.len(),
+                output
+            );
         }
+        None => eprintln!("{} does not reproduce the problem", args[2]),
     }
 }
 
+// inputs from input/ at compile time, if built with the `embed-inputs` feature
+const EMBEDDED_INPUTS: &[(usize, &str)] = include!(concat!(env!("OUT_DIR"), "/embedded_inputs.rs"));
+
+// A day's input from input/dayN.txt, falling back to the embedded copy if there is no such file.
+fn read_input(day: usize) -> Option<String> {
+    read_file(&format!("input/day{}.txt", day))
+        .ok()
+        .or_else(|| {
+            EMBEDDED_INPUTS
+                .iter()
+                .find(|(embedded_day, _)| *embedded_day == day)
+                .map(|(_, input)| input.to_string())
+        })
+}
+
 fn read_file(filename: &str) -> Result<String, std::io::Error> {
     let mut f = std::fs::File::open(filename)?;
     let mut input = String::new();
@@ -0,0 +1,164 @@
+// Shrink an input which makes a solver misbehave down to a minimal reproducer, using delta
+// debugging (ddmin).  Inputs are split into lines, except day 1 which is split into its
+// comma-separated instructions.
+
+use std::panic;
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+use adventofcode_2016::{implementations, input};
+
+const TIME_LIMIT: Duration = Duration::from_secs(10);
+
+#[derive(Clone, Copy)]
+pub enum Predicate {
+    // the default implementation panics
+    Panic,
+    // the implementations of the day don't all give the same answers (or don't all panic)
+    Mismatch,
+    // any implementation panics other than by rejecting the input as invalid
+    Crash,
+}
+
+impl Predicate {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "panic" => Some(Predicate::Panic),
+            "mismatch" => Some(Predicate::Mismatch),
+            "crash" => Some(Predicate::Crash),
+            _ => None,
+        }
+    }
+
+    // Solvers can take practically forever on inputs with a few huge numbers left in them, so
+    // each candidate runs on a thread of its own, and is abandoned and taken not to hold if it
+    // runs out of time.
+    fn holds(self, day: usize, input: &str, time_limit: Duration) -> bool {
+        let (tx, rx) = mpsc::channel();
+        let input = input.to_string();
+        thread::spawn(move || {
+            let _ = tx.send(self.check(day, &input));
+        });
+        rx.recv_timeout(time_limit).unwrap_or(false)
+    }
+
+    fn check(self, day: usize, input: &str) -> bool {
+        let solve = |new: adventofcode_2016::Constructor| {
+            panic::catch_unwind(|| {
+                let solver = new(input);
+                [solver.part1(), solver.part2()]
+            })
+            .ok()
+        };
+        let implementations = implementations(day);
+        match self {
+            Predicate::Panic => implementations
+                .first()
+                .is_some_and(|&(_, new)| solve(new).is_none()),
+            Predicate::Mismatch => {
+                let answers: Vec<_> = implementations.iter().map(|&(_, new)| solve(new)).collect();
+                answers.iter().any(|answer| *answer != answers[0])
+            }
+            Predicate::Crash => crate::fuzz::crash(day, input).is_some(),
+        }
+    }
+}
+
+// The smallest input found for which the predicate still holds, or None if it doesn't hold for
+// the original input.
+pub fn minimise(day: usize, input: &str, predicate: Predicate) -> Option<String> {
+    let (items, separator, terminator) = if day == 1 {
+        (input::comma_list(input).collect::<Vec<_>>(), ", ", "\n")
+    } else {
+        (input.lines().collect(), "\n", "\n")
+    };
+    let join = |items: &[&str]| items.join(separator) + terminator;
+
+    // the solvers panicking is expected while minimising, so don't report each one
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let minimal = if predicate.holds(day, &join(&items), TIME_LIMIT) {
+        Some(join(&ddmin(items, |items| {
+            predicate.holds(day, &join(items), TIME_LIMIT)
+        })))
+    } else {
+        None
+    };
+    panic::set_hook(hook);
+    minimal
+}
+
+// Zeller's ddmin: try each of n subsets and their complements, keeping the first which still
+// fails, and increase the granularity when none do.  The result is 1-minimal: removing any single
+// item makes the test pass.
+fn ddmin<T, F>(mut items: Vec<T>, fails: F) -> Vec<T>
+where
+    T: Clone,
+    F: Fn(&[T]) -> bool,
+{
+    let mut n = 2;
+    while items.len() >= 2 {
+        let chunk_size = items.len().div_ceil(n);
+        let chunks: Vec<Vec<T>> = items.chunks(chunk_size).map(|c| c.to_vec()).collect();
+
+        if let Some(subset) = chunks.iter().find(|subset| fails(subset)) {
+            items = subset.clone();
+            n = 2;
+            continue;
+        }
+
+        let complement = (0..chunks.len())
+            .map(|skip| {
+                chunks
+                    .iter()
+                    .enumerate()
+                    .filter(|&(i, _)| i != skip)
+                    .flat_map(|(_, chunk)| chunk.iter().cloned())
+                    .collect::<Vec<T>>()
+            })
+            .find(|complement| fails(complement));
+        if let Some(complement) = complement {
+            items = complement;
+            n = (n - 1).max(2);
+            continue;
+        }
+
+        if n >= items.len() {
+            break;
+        }
+        n = (n * 2).min(items.len());
+    }
+    items
+}
+
+#[test]
+fn test() {
+    let items: Vec<u32> = (1..=20).collect();
+    assert_eq!(
+        vec![3, 17],
+        ddmin(items, |items| items.contains(&3) && items.contains(&17))
+    );
+
+    // day 3 panics on a row which doesn't have three sides
+    #[cfg(feature = "day3")]
+    {
+        assert_eq!(
+            Some("4\n".to_string()),
+            minimise(3, "1 2 3\n4\n5 6 7\n", Predicate::Panic)
+        );
+        assert_eq!(None, minimise(3, "1 2 3\n", Predicate::Panic));
+    }
+    // day 1 instructions may be split over lines, with or without spaces after the commas; a
+    // single instruction never revisits a block, so part 2 panics on it
+    #[cfg(feature = "day1")]
+    assert_eq!(
+        Some("R1\n".to_string()),
+        minimise(1, "R1,\nX2,L3\n", Predicate::Panic)
+    );
+
+    // a candidate which takes too long doesn't count, here because finding day 5's password takes
+    // millions of hashes
+    #[cfg(feature = "day5")]
+    assert!(!Predicate::Panic.holds(5, "abc", Duration::from_millis(10)));
+}
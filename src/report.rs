@@ -0,0 +1,156 @@
+// Printing of answers and timings.  On a terminal, answers are coloured by whether they match the
+// known answer in input/dayN.partP.answer (green if so, red if not, yellow if there's no such
+// file) and parts slower than the threshold are highlighted.  Output stays plain when piped, or if
+// NO_COLOR is set.
+
+use std::io::IsTerminal;
+
+pub const DEFAULT_SLOW: f64 = 1.0;
+
+const GREEN: &str = "32";
+const RED: &str = "31";
+const YELLOW: &str = "33";
+const BOLD_RED: &str = "1;31";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Status {
+    Correct,
+    Wrong,
+    Unknown,
+}
+
+impl Status {
+    fn check(day: usize, part: usize, answer: &str) -> Self {
+        match std::fs::read_to_string(format!("input/day{}.part{}.answer", day, part)) {
+            Ok(expected) => Status::compare(&expected, answer),
+            Err(_) => Status::Unknown,
+        }
+    }
+
+    // trailing whitespace is ignored, since editors are apt to add or remove it
+    fn compare(expected: &str, answer: &str) -> Self {
+        if expected
+            .trim_end()
+            .lines()
+            .map(str::trim_end)
+            .eq(answer.trim_end().lines().map(str::trim_end))
+        {
+            Status::Correct
+        } else {
+            Status::Wrong
+        }
+    }
+
+    fn colour(self) -> &'static str {
+        match self {
+            Status::Correct => GREEN,
+            Status::Wrong => RED,
+            Status::Unknown => YELLOW,
+        }
+    }
+}
+
+struct Timing {
+    day: usize,
+    part: usize,
+    status: Status,
+    elapsed: f64,
+}
+
+pub struct Report {
+    colour: bool,
+    slow: f64,
+    timings: Vec<Timing>,
+}
+
+impl Report {
+    pub fn new(slow: f64) -> Self {
+        Self {
+            colour: std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none(),
+            slow,
+            timings: vec![],
+        }
+    }
+
+    // print the answer to one part; multi-line answers continue below, lined up with the first
+    pub fn part(&mut self, day: usize, part: usize, answer: &str, elapsed: f64) {
+        let status = Status::check(day, part, answer);
+        let mut lines = answer.lines();
+        println!(
+            "Day {:02}, part {}: {} {}",
+            day,
+            part,
+            self.paint(
+                status.colour(),
+                &format!("{:56}", lines.next().unwrap_or(""))
+            ),
+            self.time(elapsed)
+        );
+        for line in lines {
+            println!("{:16}{}", "", self.paint(status.colour(), line));
+        }
+        self.timings.push(Timing {
+            day,
+            part,
+            status,
+            elapsed,
+        });
+    }
+
+    // every part printed so far, slowest first
+    pub fn summary(&self) {
+        let mut timings: Vec<&Timing> = self.timings.iter().collect();
+        timings.sort_by(|a, b| b.elapsed.total_cmp(&a.elapsed));
+        println!();
+        println!("Day  Part  Status    Time");
+        for timing in timings {
+            println!(
+                "{:3}  {:4}  {}  {}",
+                timing.day,
+                timing.part,
+                self.paint(
+                    timing.status.colour(),
+                    &format!("{:8}", format!("{:?}", timing.status))
+                ),
+                self.time(timing.elapsed)
+            );
+        }
+    }
+
+    fn time(&self, elapsed: f64) -> String {
+        let time = format!("{:.3}s", elapsed);
+        if elapsed >= self.slow {
+            self.paint(BOLD_RED, &time)
+        } else {
+            time
+        }
+    }
+
+    fn paint(&self, colour: &str, s: &str) -> String {
+        if self.colour {
+            format!("\x1b[{}m{}\x1b[0m", colour, s)
+        } else {
+            s.to_string()
+        }
+    }
+}
+
+#[test]
+fn test() {
+    assert_eq!(Status::Correct, Status::compare("123\n", "123"));
+    assert_eq!(Status::Correct, Status::compare("# #  \n #\n", "# #\n #"));
+    assert_eq!(Status::Wrong, Status::compare("123\n", "124"));
+    assert_eq!(Status::Wrong, Status::compare("# #\n #\n", "# #"));
+
+    let mut report = Report {
+        colour: false,
+        slow: 1.0,
+        timings: vec![],
+    };
+    assert_eq!("1.500s", report.time(1.5));
+    assert_eq!("ok", report.paint(GREEN, "ok"));
+    report.colour = true;
+    assert_eq!("0.500s", report.time(0.5));
+    assert_eq!("\x1b[1;31m1.500s\x1b[0m", report.time(1.5));
+    assert_eq!("\x1b[32mok\x1b[0m", report.paint(GREEN, "ok"));
+}
@@ -0,0 +1,117 @@
+// Measure how a day's solver scales by timing it on generated inputs of increasing size, then fit
+// time = c * size^k to estimate the growth exponent k of each part.  An exponent much above 1 for
+// a puzzle which should be linear suggests something is accidentally quadratic.
+
+use std::time::Instant;
+
+use adventofcode_2016::generate::{self, Rng};
+use adventofcode_2016::implementations;
+
+const MIN_SIZE: usize = 1000;
+
+// exponents above this are highlighted as suspicious
+const SUPERLINEAR: f64 = 1.5;
+
+pub fn scale(day: usize, max_size: usize) {
+    let generate = match generate::generator(day) {
+        Some(generate) => generate,
+        None => {
+            eprintln!("No generator for day {}", day);
+            return;
+        }
+    };
+    let new = match implementations(day).first() {
+        Some(&(_, new)) => new,
+        None => return,
+    };
+
+    println!(
+        "Day {:02} {:>10} {:>10} {:>10}",
+        day, "size", "part 1", "part 2"
+    );
+    let mut timings = [vec![], vec![]];
+    let mut size = MIN_SIZE;
+    while size <= max_size {
+        let input = generate(&mut Rng::new(1), size);
+        let [t1, t2] = best_of(|| {
+            let t0 = Instant::now();
+            let solver = new(&input);
+            solver.part1();
+            let t1 = t0.elapsed().as_secs_f64();
+            let t0 = Instant::now();
+            solver.part2();
+            [t1, t0.elapsed().as_secs_f64()]
+        });
+        println!("{:6} {:>10} {:>9.3}s {:>9.3}s", "", size, t1, t2);
+        timings[0].push((size as f64, t1));
+        timings[1].push((size as f64, t2));
+        size *= 10;
+    }
+
+    let exponents = timings.map(|points| growth_exponent(&points));
+    print!("{:6} {:>10}", "", "exponent");
+    for k in exponents.iter() {
+        match k {
+            Some(k) => print!(" {:>10.2}", k),
+            None => print!(" {:>10}", "-"),
+        }
+    }
+    println!();
+    for (part, k) in exponents.iter().enumerate() {
+        if let Some(k) = k.filter(|&k| k > SUPERLINEAR) {
+            println!(
+                "Day {:02}, part {} looks superlinear: time grows as size^{:.2}",
+                day,
+                part + 1,
+                k
+            );
+        }
+    }
+}
+
+// Run f a few times (while it's cheap) and keep the fastest time for each part, to reduce noise
+// on the smaller inputs.
+fn best_of<F>(f: F) -> [f64; 2]
+where
+    F: Fn() -> [f64; 2],
+{
+    let t0 = Instant::now();
+    let mut best = f();
+    for _ in 1..5 {
+        if t0.elapsed().as_secs_f64() > 0.2 {
+            break;
+        }
+        let times = f();
+        best = [best[0].min(times[0]), best[1].min(times[1])];
+    }
+    best
+}
+
+// Least squares fit of log(time) against log(size); the slope is the exponent.
+fn growth_exponent(points: &[(f64, f64)]) -> Option<f64> {
+    let logs: Vec<(f64, f64)> = points
+        .iter()
+        .filter(|&&(_, time)| time > 0.0)
+        .map(|&(size, time)| (size.ln(), time.ln()))
+        .collect();
+    if logs.len() < 2 {
+        return None;
+    }
+    let n = logs.len() as f64;
+    let mean_x = logs.iter().map(|&(x, _)| x).sum::<f64>() / n;
+    let mean_y = logs.iter().map(|&(_, y)| y).sum::<f64>() / n;
+    let covariance: f64 = logs.iter().map(|&(x, y)| (x - mean_x) * (y - mean_y)).sum();
+    let variance: f64 = logs.iter().map(|&(x, _)| (x - mean_x).powi(2)).sum();
+    Some(covariance / variance)
+}
+
+#[test]
+fn test() {
+    let linear = [(1000.0, 0.001), (10000.0, 0.01), (100000.0, 0.1)];
+    assert!((growth_exponent(&linear).unwrap() - 1.0).abs() < 1e-9);
+
+    let quadratic = [(1000.0, 0.001), (10000.0, 0.1), (100000.0, 10.0)];
+    assert!((growth_exponent(&quadratic).unwrap() - 2.0).abs() < 1e-9);
+
+    assert_eq!(None, growth_exponent(&[(1000.0, 0.001)]));
+}
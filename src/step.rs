@@ -0,0 +1,64 @@
+// Interactive stepping through the day 1 walk.  Commands are read a line at a time:
+//
+//     n, or an empty line   next block          p   previous block
+//     i                     next instruction    u   previous instruction
+//     r                     first revisit       s   start
+//     e                     end                 q   quit
+
+use std::io::{BufRead, Write};
+
+use adventofcode_2016::day1::Stepper;
+
+const HELP: &str = "n: next block, p: previous block, i: next instruction, \
+                    u: previous instruction, r: first revisit, s: start, e: end, q: quit";
+
+pub fn step(input: &str, commands: impl BufRead, mut out: impl Write) {
+    let mut stepper = Stepper::new(input);
+    writeln!(out, "{}", stepper).unwrap();
+    for command in commands.lines() {
+        let moved = match command.unwrap().trim() {
+            "" | "n" => stepper.next_block(),
+            "p" => stepper.previous_block(),
+            "i" => stepper.next_instruction(),
+            "u" => stepper.previous_instruction(),
+            "r" => stepper.first_revisit(),
+            "s" => {
+                stepper.start();
+                true
+            }
+            "e" => {
+                stepper.end();
+                true
+            }
+            "q" => break,
+            _ => {
+                writeln!(out, "{}", HELP).unwrap();
+                continue;
+            }
+        };
+        if moved {
+            writeln!(out, "{}", stepper).unwrap();
+        } else {
+            writeln!(out, "(can't go any further)").unwrap();
+        }
+    }
+}
+
+#[test]
+fn test() {
+    let mut out = vec![];
+    step("R2, L1", "i\n\nn\np\nx\nq\ni\n".as_bytes(), &mut out);
+    assert_eq!(
+        "\
+start        block 0/3  heading North  position (0, 0)  distance 0  visits 1  visited 1
+R2       #1  block 2/3  heading East  position (2, 0)  distance 2  visits 1  visited 3
+L1       #2  block 3/3  heading North  position (2, 1)  distance 3  visits 1  visited 4
+(can't go any further)
+R2       #1  block 2/3  heading East  position (2, 0)  distance 2  visits 1  visited 3
+"
+        .to_string()
+            + HELP
+            + "\n",
+        String::from_utf8(out).unwrap()
+    );
+}
@@ -0,0 +1,158 @@
+// Structured trace events from inside solvers, written as JSON lines when enabled with `--trace`
+// (or `--trace=FILE`).  When tracing is off an event costs a single flag check; its fields are not
+// evaluated.
+//
+//     trace::event!("day1.position", x = 3, y = -2, heading = "North");
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::panic;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+
+pub const DEFAULT_FILE: &str = "trace.jsonl";
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static WRITER: OnceLock<Mutex<BufWriter<File>>> = OnceLock::new();
+
+pub fn enable(filename: &str) -> std::io::Result<()> {
+    let file = File::create(filename)?;
+    if WRITER.set(Mutex::new(BufWriter::new(file))).is_ok() {
+        flush_on_panic(WRITER.get().unwrap());
+        ENABLED.store(true, Ordering::Relaxed);
+    }
+    Ok(())
+}
+
+// Statics are never dropped, so without this the events leading up to a solver panicking, which
+// are the ones most worth seeing, would be left in the buffer.  If the panic happened while writing
+// an event the writer is still locked, and what has been buffered is lost.
+fn flush_on_panic(writer: &'static Mutex<BufWriter<File>>) {
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        if let Ok(mut writer) = writer.try_lock() {
+            let _ = writer.flush();
+        }
+        hook(info);
+    }));
+}
+
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+pub fn emit(name: &str, fields: &[(&str, &dyn Value)]) {
+    if let Some(writer) = WRITER.get() {
+        let line = format_event(name, fields);
+        writeln!(writer.lock().unwrap(), "{}", line).unwrap();
+    }
+}
+
+pub fn flush() {
+    if let Some(writer) = WRITER.get() {
+        writer.lock().unwrap().flush().unwrap();
+    }
+}
+
+#[macro_export]
+macro_rules! trace_event {
+    ($name:expr $(, $key:ident = $value:expr)* $(,)?) => {
+        if $crate::trace::enabled() {
+            $crate::trace::emit(
+                $name,
+                &[$((stringify!($key), &$value as &dyn $crate::trace::Value)),*],
+            );
+        }
+    };
+}
+pub use crate::trace_event as event;
+
+fn format_event(name: &str, fields: &[(&str, &dyn Value)]) -> String {
+    let mut line = String::from("{\"event\":");
+    name.write_json(&mut line);
+    for (key, value) in fields {
+        line.push(',');
+        key.write_json(&mut line);
+        line.push(':');
+        value.write_json(&mut line);
+    }
+    line.push('}');
+    line
+}
+
+// A value which can be written as a trace event field.
+pub trait Value {
+    fn write_json(&self, out: &mut String);
+}
+
+macro_rules! number_value {
+    ($($t:ty),*) => {
+        $(impl Value for $t {
+            fn write_json(&self, out: &mut String) {
+                out.push_str(&self.to_string());
+            }
+        })*
+    };
+}
+
+number_value!(i32, i64, u8, u32, u64, usize, bool);
+
+impl Value for str {
+    fn write_json(&self, out: &mut String) {
+        out.push('"');
+        for c in self.chars() {
+            match c {
+                '"' => out.push_str("\\\""),
+                '\\' => out.push_str("\\\\"),
+                '\n' => out.push_str("\\n"),
+                c if c.is_control() => out.push_str(&format!("\\u{:04x}", c as u32)),
+                c => out.push(c),
+            }
+        }
+        out.push('"');
+    }
+}
+
+impl Value for &str {
+    fn write_json(&self, out: &mut String) {
+        (*self).write_json(out)
+    }
+}
+
+impl Value for String {
+    fn write_json(&self, out: &mut String) {
+        self.as_str().write_json(out)
+    }
+}
+
+impl Value for char {
+    fn write_json(&self, out: &mut String) {
+        self.to_string().write_json(out)
+    }
+}
+
+#[test]
+fn test() {
+    assert_eq!(
+        r#"{"event":"day1.position","x":3,"y":-2,"heading":"North"}"#,
+        format_event(
+            "day1.position",
+            &[("x", &3), ("y", &-2), ("heading", &"North")]
+        )
+    );
+    assert_eq!(
+        r#"{"event":"quote","text":"a \"b\"\n\\ \u0007"}"#,
+        format_event("quote", &[("text", &"a \"b\"\n\\ \u{7}".to_string())])
+    );
+
+    // buffered events are written out when something panics
+    let filename = std::env::temp_dir().join(format!("trace-test-{}.jsonl", std::process::id()));
+    let writer: &'static Mutex<_> = Box::leak(Box::new(Mutex::new(BufWriter::new(
+        File::create(&filename).unwrap(),
+    ))));
+    writeln!(writer.lock().unwrap(), "{{}}").unwrap();
+    flush_on_panic(writer);
+    assert!(panic::catch_unwind(|| panic!("solver bug")).is_err());
+    assert_eq!("{}\n", std::fs::read_to_string(&filename).unwrap());
+    std::fs::remove_file(filename).unwrap();
+}
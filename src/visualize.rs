@@ -0,0 +1,67 @@
+// Pictures of a day's solution, drawn after running a single day with --visualize:
+//
+//     day 1   a map of the walk, or an SVG of it with --visualize=FILE
+//     day 2   each keypad after every line of instructions, written to FILE with --visualize=FILE
+//
+// With --animate, day 2 is instead redrawn after every move, or every move is written to FILE.
+
+#[cfg_attr(not(all(feature = "day1", feature = "day2")), allow(unused_variables))]
+pub fn visualize(day: usize, input: &str, filename: Option<&str>, animate: bool) {
+    match day {
+        #[cfg(feature = "day1")]
+        1 => {
+            use adventofcode_2016::day1::{path, Geometry};
+
+            let path = path(input, Geometry::Square);
+            match filename {
+                Some(filename) => std::fs::write(filename, path.svg()).unwrap(),
+                None => print!("{}", path.ascii(80, 40)),
+            }
+        }
+        #[cfg(feature = "day2")]
+        2 => {
+            use adventofcode_2016::day2::{trace, Keypad, DIAMOND_KEYPAD, NORMAL_KEYPAD};
+
+            let mut text = String::new();
+            for (part, layout) in [(1, NORMAL_KEYPAD), (2, DIAMOND_KEYPAD)] {
+                let lines = trace(input, &Keypad::new(layout, '5'));
+                for (i, frames) in lines.iter().enumerate() {
+                    let label = format!("Part {}, line {}/{}", part, i + 1, lines.len());
+                    let frames = if animate {
+                        &frames[..]
+                    } else {
+                        &frames[frames.len() - 1..]
+                    };
+                    for frame in frames {
+                        match filename {
+                            Some(_) => text += &format!("{}\n{}\n", label, frame),
+                            None if animate => show(&label, frame),
+                            None => println!("{}\n{}", label, frame),
+                        }
+                    }
+                }
+            }
+            if let Some(filename) = filename {
+                std::fs::write(filename, text).unwrap();
+            }
+        }
+        _ => eprintln!("No visualisation for day {}", day),
+    }
+}
+
+// one frame of an animation, drawn over the last on a terminal
+#[cfg(feature = "day2")]
+fn show(label: &str, frame: &str) {
+    use std::io::{IsTerminal, Write};
+
+    const FRAME_TIME: std::time::Duration = std::time::Duration::from_millis(50);
+
+    let mut stdout = std::io::stdout();
+    if stdout.is_terminal() {
+        print!("\x1b[2J\x1b[H{}\n{}", label, frame);
+        stdout.flush().unwrap();
+        std::thread::sleep(FRAME_TIME);
+    } else {
+        println!("{}\n{}", label, frame);
+    }
+}